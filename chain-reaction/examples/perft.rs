@@ -0,0 +1,52 @@
+// conta le sequenze di mosse dalla posizione iniziale con tutti i backend e le confronta con i
+// conteggi di riferimento
+//
+// cargo run --release --example perft -- 4 4 2 5
+
+use std::{env, process, time::Instant};
+
+use chain_reaction::{bitboard::FastGame, perft::KNOWN, Game};
+
+fn main() {
+    let args: Vec<usize> = env::args()
+        .skip(1)
+        .map(|arg| {
+            arg.parse()
+                .expect("usage: perft [height] [width] [players] [depth]")
+        })
+        .collect();
+    let height = args.first().copied().unwrap_or(3);
+    let width = args.get(1).copied().unwrap_or(height);
+    let players = args.get(2).copied().unwrap_or(2);
+    let depth = args.get(3).copied().unwrap_or(5);
+    let Some(game) = Game::new(height, width, players) else {
+        eprintln!("invalid game {}x{} with {} players", height, width, players);
+        process::exit(1);
+    };
+    let fast = FastGame::from(&game);
+    let known = KNOWN
+        .iter()
+        .find(|known| (known.height, known.width, known.players) == (height, width, players));
+    let mut failed = false;
+    for depth in 1..=depth {
+        let start = Instant::now();
+        let count = game.perft(depth);
+        let elapsed = start.elapsed();
+        let fast_count = fast.perft(depth);
+        let expected = known.and_then(|known| known.counts.get(depth - 1));
+        let ok = fast_count == count && expected.is_none_or(|&expected| expected == count);
+        failed |= !ok;
+        println!(
+            "perft({}) = {:>12} in {:>10.2?}  fast {:>12}  expected {:>12}  {}",
+            depth,
+            count,
+            elapsed,
+            fast_count,
+            expected.map_or("-".to_string(), u64::to_string),
+            if ok { "ok" } else { "MISMATCH" }
+        );
+    }
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,39 @@
+// risolve la posizione iniziale di una scacchiera piccola a due giocatori
+//
+// cargo run --release --example solve -- 3 4
+
+use std::{env, process, time::Instant};
+
+use chain_reaction::{
+    solver::{Outcome, Solver},
+    Game,
+};
+
+fn main() {
+    let args: Vec<usize> = env::args()
+        .skip(1)
+        .map(|arg| arg.parse().expect("usage: solve [height] [width]"))
+        .collect();
+    let height = args.first().copied().unwrap_or(3);
+    let width = args.get(1).copied().unwrap_or(height);
+    let Some(game) = Game::new(height, width, 2) else {
+        eprintln!("invalid board size {}x{}", height, width);
+        process::exit(1);
+    };
+    let start = Instant::now();
+    let mut solver = Solver::default();
+    let solution = solver.solve(&game).unwrap();
+    let result = match solution.outcome {
+        Outcome::Win => "wins",
+        Outcome::Loss => "loses",
+    };
+    println!(
+        "{}x{}: the first player {}, optimal moves {:?}",
+        height, width, result, solution.moves
+    );
+    println!(
+        "{} positions solved in {:.2?}",
+        solver.positions(),
+        start.elapsed()
+    );
+}
@@ -0,0 +1,40 @@
+// genera la tabella dei finali di una scacchiera piccola e la salva su file
+//
+// cargo run --release --example tablebase -- 3 3 6 3x3.crtb
+
+use std::{env, fs::File, io::BufWriter, process, time::Instant};
+
+use chain_reaction::tablebase::Tablebase;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let number = |i: usize, default: usize| {
+        args.get(i).map_or(default, |arg| {
+            arg.parse()
+                .expect("usage: tablebase [height] [width] [max atoms] [file]")
+        })
+    };
+    let (height, width, max_atoms) = (number(0, 3), number(1, 3), number(2, 6));
+    let start = Instant::now();
+    let Some(tablebase) = Tablebase::generate(height, width, max_atoms as u16) else {
+        eprintln!("invalid board size {}x{}", height, width);
+        process::exit(1);
+    };
+    println!(
+        "{}x{}: {} positions with at most {} atoms in {:.2?}",
+        height,
+        width,
+        tablebase.len(),
+        max_atoms,
+        start.elapsed()
+    );
+    let path = args
+        .get(3)
+        .cloned()
+        .unwrap_or_else(|| format!("{}x{}.crtb", height, width));
+    let file = File::create(&path).expect("cannot create the tablebase file");
+    tablebase
+        .write_to(BufWriter::new(file))
+        .expect("cannot write the tablebase file");
+    println!("written to {}", path);
+}
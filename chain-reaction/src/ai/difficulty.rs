@@ -0,0 +1,210 @@
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+
+use crate::{
+    ai::{
+        eval::{Critical, Edges, Evaluator, Material, Weighted},
+        mcts::{MctsBot, Rollout},
+        search::{SearchBot, Strategy},
+        Bot, Budget,
+    },
+    zobrist, Coord, Game,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Engine {
+    // guarda solo la posizione dopo ciascuna delle proprie mosse; `horizon` è il numero di
+    // round di esplosioni visti, le reazioni più lunghe vengono troncate
+    Shallow { noise: i32, horizon: Option<usize> },
+    Search { strategy: Strategy, noise: i32 },
+    Mcts(Rollout),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub engine: Engine,
+    pub budget: Budget,
+    // probabilità di giocare una mossa a caso
+    pub blunder: f64,
+}
+
+// valutazione con un rumore che dipende solo dalla posizione e dal seme, così la stessa
+// posizione vale sempre uguale e le partite sono riproducibili
+#[derive(Clone, Debug)]
+pub struct Noisy<E> {
+    pub evaluator: E,
+    pub amplitude: i32,
+    pub seed: u64,
+}
+
+// giocatore con un comportamento più umano a seconda della difficoltà; a parità di seme
+// gioca sempre le stesse mosse
+pub struct DifficultyBot {
+    pub profile: Profile,
+    rng: StdRng,
+    seed: u64,
+    engine: Option<Box<dyn Bot>>,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Beginner,
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    pub fn profile(self) -> Profile {
+        match self {
+            Difficulty::Beginner => Profile {
+                engine: Engine::Shallow {
+                    noise: 8,
+                    horizon: Some(1),
+                },
+                budget: Budget::default(),
+                blunder: 0.25,
+            },
+            Difficulty::Easy => Profile {
+                engine: Engine::Shallow {
+                    noise: 4,
+                    horizon: Some(3),
+                },
+                budget: Budget::default(),
+                blunder: 0.1,
+            },
+            Difficulty::Medium => Profile {
+                engine: Engine::Search {
+                    strategy: Strategy::Paranoid,
+                    noise: 4,
+                },
+                budget: Budget::nodes(2_000),
+                blunder: 0.03,
+            },
+            Difficulty::Hard => Profile {
+                engine: Engine::Mcts(Rollout::Random),
+                budget: Budget::nodes(2_000),
+                blunder: 0.0,
+            },
+            Difficulty::Expert => Profile {
+                engine: Engine::Mcts(Rollout::Heuristic),
+                budget: Budget::nodes(10_000),
+                blunder: 0.0,
+            },
+        }
+    }
+}
+
+impl<E: Evaluator> Evaluator for Noisy<E> {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        let value = self.evaluator.evaluate(game, player);
+        if self.amplitude == 0 {
+            return value;
+        }
+        let hash = zobrist::mix(game.zobrist() ^ self.seed ^ player as u64);
+        let range = 2 * self.amplitude.unsigned_abs() as u64 + 1;
+        value + (hash % range) as i32 - self.amplitude.abs()
+    }
+}
+
+impl DifficultyBot {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self::with_profile(difficulty.profile(), seed)
+    }
+
+    pub fn with_profile(profile: Profile, seed: u64) -> Self {
+        let engine: Option<Box<dyn Bot>> = match profile.engine {
+            Engine::Shallow { .. } => None,
+            Engine::Search { strategy, noise } => Some(Box::new(SearchBot::with_evaluator(
+                strategy,
+                profile.budget,
+                Noisy {
+                    evaluator: Weighted::standard(),
+                    amplitude: noise,
+                    seed,
+                },
+            ))),
+            Engine::Mcts(rollout) => {
+                let mut bot = MctsBot::new(profile.budget, seed);
+                bot.rollout = rollout;
+                Some(Box::new(bot))
+            }
+        };
+        Self {
+            profile,
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            engine,
+        }
+    }
+
+    fn shallow(&mut self, game: &Game, noise: i32, horizon: Option<usize>) -> Coord {
+        let player = game.turn();
+        let evaluator = Noisy {
+            evaluator: Weighted::new(vec![
+                (4, Box::new(Material)),
+                (3, Box::new(Critical)),
+                (1, Box::new(Edges)),
+            ]),
+            amplitude: noise,
+            seed: self.seed,
+        };
+        let mut best = vec![];
+        let mut best_score = i32::MIN;
+        for coord in game.legal_moves() {
+            let preview = game.preview(coord).unwrap();
+            let score = match horizon {
+                // la reazione continua oltre quello che il bot riesce a vedere; con orizzonte 0
+                // vede solo l'atomo aggiunto, prima che la cella esploda
+                Some(horizon) if preview.explosions.len() > horizon => {
+                    let board = match horizon {
+                        0 => {
+                            let mut board = game.board.clone();
+                            let cell = &mut board[coord.0 * game.width() + coord.1];
+                            cell.set_player(player);
+                            cell.add_atom();
+                            board
+                        }
+                        _ => preview.explosions[horizon - 1].result.clone(),
+                    };
+                    evaluator.evaluate(&game.with_board(board), player)
+                }
+                _ if preview.game.won() => i32::MAX,
+                _ => evaluator.evaluate(&preview.game, player),
+            };
+            if score > best_score {
+                best_score = score;
+                best.clear();
+            }
+            if score == best_score {
+                best.push(coord);
+            }
+        }
+        best.into_iter().choose(&mut self.rng).unwrap()
+    }
+}
+
+impl Bot for DifficultyBot {
+    fn choose_move(&mut self, game: &Game) -> Coord {
+        if self.profile.blunder > 0.0 && self.rng.gen_bool(self.profile.blunder) {
+            return game
+                .legal_moves()
+                .choose(&mut self.rng)
+                .expect("no legal moves, the game is already won");
+        }
+        match (&mut self.engine, self.profile.engine) {
+            (Some(engine), _) => engine.choose_move(game),
+            (None, Engine::Shallow { noise, horizon }) => self.shallow(game, noise, horizon),
+            (None, _) => unreachable!("only the shallow engine has no bot"),
+        }
+    }
+}
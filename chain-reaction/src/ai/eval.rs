@@ -0,0 +1,129 @@
+use crate::{analysis::capture_threats, Coord, Game};
+
+// valuta una posizione dal punto di vista di `player`: più alto è meglio. Le posizioni già
+// vinte sono gestite da chi cerca, qui conta solo l'euristica
+pub trait Evaluator {
+    fn evaluate(&self, game: &Game, player: usize) -> i32;
+}
+
+// i propri atomi meno quelli degli avversari
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Material;
+
+// celle proprie a un atomo dall'esplosione, meno quelle degli avversari
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Critical;
+
+// celle degli avversari vicine a una propria cella critica, meno le proprie vicine a una
+// cella critica di un avversario
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Threats;
+
+// controllo di angoli (2 punti) e bordi (1 punto), che esplodono con meno atomi
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Edges;
+
+// celle proprie che un avversario può catturare con la sua prossima mossa, in negativo
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Unstable;
+
+// somma pesata di più euristiche
+#[derive(Default)]
+pub struct Weighted {
+    pub terms: Vec<(i32, Box<dyn Evaluator>)>,
+}
+
+impl Evaluator for Material {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        game.players[player].atoms as i32 * 2 - game.atoms as i32
+    }
+}
+
+impl Evaluator for Critical {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        game.cells()
+            .filter(|&coord| game.is_critical(coord))
+            .map(|coord| sign(game, coord, player))
+            .sum()
+    }
+}
+
+impl Evaluator for Threats {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        game.cells()
+            .filter(|&coord| game.get(coord).atoms() != 0)
+            .filter(|&coord| {
+                let owner = game.get(coord).player();
+                game.neighbours(coord)
+                    .any(|next| game.is_critical(next) && game.get(next).player() != owner)
+            })
+            .map(|coord| -sign(game, coord, player))
+            .sum()
+    }
+}
+
+impl Evaluator for Edges {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        game.cells()
+            .filter(|&coord| game.get(coord).atoms() != 0)
+            .map(|coord| (4 - game.get(coord).max_atoms() as i32) * sign(game, coord, player))
+            .sum()
+    }
+}
+
+impl Evaluator for Unstable {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        -(capturable(game, player).len() as i32)
+    }
+}
+
+impl Evaluator for Weighted {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        self.terms
+            .iter()
+            .map(|(weight, evaluator)| weight * evaluator.evaluate(game, player))
+            .sum()
+    }
+}
+
+impl Weighted {
+    pub fn new(terms: Vec<(i32, Box<dyn Evaluator>)>) -> Self {
+        Self { terms }
+    }
+
+    // una combinazione ragionevole di tutte le euristiche
+    pub fn standard() -> Self {
+        Self::new(vec![
+            (4, Box::new(Material)),
+            (3, Box::new(Critical)),
+            (2, Box::new(Threats)),
+            (1, Box::new(Edges)),
+            (2, Box::new(Unstable)),
+        ])
+    }
+}
+
+impl<E: Evaluator + ?Sized> Evaluator for Box<E> {
+    fn evaluate(&self, game: &Game, player: usize) -> i32 {
+        (**self).evaluate(game, player)
+    }
+}
+
+fn sign(game: &Game, coord: Coord, player: usize) -> i32 {
+    if game.get(coord).player() == player {
+        1
+    } else {
+        -1
+    }
+}
+
+// le celle di `player` che almeno un avversario cattura con una sola mossa
+pub(crate) fn capturable(game: &Game, player: usize) -> Vec<Coord> {
+    let mut captured: Vec<_> = capture_threats(game, player)
+        .into_iter()
+        .flat_map(|(_, captured)| captured)
+        .collect();
+    captured.sort_unstable();
+    captured.dedup();
+    captured
+}
@@ -0,0 +1,48 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{ai::Bot, Coord, Game};
+
+// gioca la mossa che vince subito o, altrimenti, quella che gli fa guadagnare più atomi
+// (il proprio più quelli catturati) a reazione conclusa; a parità sceglie a caso
+#[derive(Clone, Debug)]
+pub struct GreedyBot {
+    rng: StdRng,
+}
+
+impl GreedyBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Bot for GreedyBot {
+    fn choose_move(&mut self, game: &Game) -> Coord {
+        let player = game.turn();
+        let before = game.players[player].atoms;
+        let mut game = game.snapshot();
+        let moves: Vec<_> = game.legal_moves().collect();
+        let mut best = vec![];
+        let mut best_score = None;
+        for coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            let score = if game.won() {
+                u16::MAX
+            } else {
+                game.players[player].atoms - before
+            };
+            game.unmake_move(undo);
+            if best_score.is_none_or(|best_score| score > best_score) {
+                best_score = Some(score);
+                best.clear();
+            }
+            if best_score == Some(score) {
+                best.push(coord);
+            }
+        }
+        *best
+            .choose(&mut self.rng)
+            .expect("no legal moves, the game is already won")
+    }
+}
@@ -0,0 +1,210 @@
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+
+use crate::{
+    ai::{Bot, Budget},
+    bitboard::FastGame,
+    Coord, Game,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rollout {
+    Random,
+    // metà delle volte fa esplodere una propria cella critica, se ce n'è una
+    Heuristic,
+}
+
+// Monte Carlo Tree Search con UCT: ogni nodo conta le vittorie del giocatore che ha fatto la
+// mossa che porta al nodo, quindi funziona con qualsiasi numero di giocatori. Il budget conta
+// le iterazioni; l'albero viene riusato alla mossa successiva se la nuova posizione è tra i
+// discendenti della radice
+#[derive(Clone, Debug)]
+pub struct MctsBot {
+    pub budget: Budget,
+    pub exploration: f64,
+    pub rollout: Rollout,
+    rng: StdRng,
+    tree: Vec<Node>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    coord: Option<Coord>,
+    // chi ha giocato `coord`
+    player: usize,
+    hash: u64,
+    children: Vec<usize>,
+    untried: Vec<Coord>,
+    visits: u32,
+    wins: f64,
+}
+
+const DEFAULT_ITERATIONS: u64 = 1_000;
+
+impl MctsBot {
+    pub fn new(budget: Budget, seed: u64) -> Self {
+        Self {
+            budget,
+            exploration: std::f64::consts::SQRT_2,
+            rollout: Rollout::Random,
+            rng: StdRng::seed_from_u64(seed),
+            tree: vec![],
+        }
+    }
+
+    // visite della radice dopo l'ultima ricerca, comprese quelle ereditate
+    pub fn visits(&self) -> u32 {
+        self.tree.first().map_or(0, |root| root.visits)
+    }
+
+    pub fn search(&mut self, game: &Game) -> Coord {
+        let mut game = game.snapshot();
+        assert!(!game.won(), "no legal moves, the game is already won");
+        self.reuse(&game);
+        let budget = if self.budget == Budget::default() {
+            Budget::nodes(DEFAULT_ITERATIONS)
+        } else {
+            self.budget
+        };
+        let mut limit = budget.start();
+        let mut undos = vec![];
+        let mut path = vec![];
+        while !limit.exhausted() {
+            limit.nodes += 1;
+            // selezione
+            let mut node = 0;
+            path.push(node);
+            while self.tree[node].untried.is_empty() && !self.tree[node].children.is_empty() {
+                node = self.select(node);
+                path.push(node);
+                undos.push(game.make_move(self.tree[node].coord.unwrap()).unwrap());
+            }
+            // espansione
+            if !self.tree[node].untried.is_empty() {
+                let index = self.rng.gen_range(0..self.tree[node].untried.len());
+                let coord = self.tree[node].untried.swap_remove(index);
+                let player = game.turn();
+                undos.push(game.make_move(coord).unwrap());
+                let child = self.tree.len();
+                self.tree.push(Node::new(&game, Some(coord), player));
+                self.tree[node].children.push(child);
+                path.push(child);
+            }
+            // simulazione
+            let winner = self.simulate(&game);
+            // retropropagazione
+            for node in path.drain(..) {
+                let node = &mut self.tree[node];
+                node.visits += 1;
+                if node.player == winner {
+                    node.wins += 1.0;
+                }
+            }
+            while let Some(undo) = undos.pop() {
+                game.unmake_move(undo);
+            }
+        }
+        let root = &self.tree[0];
+        root.children
+            .iter()
+            .map(|&child| &self.tree[child])
+            .max_by_key(|child| child.visits)
+            .and_then(|child| child.coord)
+            .unwrap_or_else(|| root.untried[0])
+    }
+
+    fn select(&self, node: usize) -> usize {
+        let parent = &self.tree[node];
+        let log = (parent.visits as f64).ln();
+        *parent
+            .children
+            .iter()
+            .max_by(|&&a, &&b| self.uct(a, log).total_cmp(&self.uct(b, log)))
+            .unwrap()
+    }
+
+    fn uct(&self, node: usize, log: f64) -> f64 {
+        let node = &self.tree[node];
+        let visits = node.visits as f64;
+        node.wins / visits + self.exploration * (log / visits).sqrt()
+    }
+
+    fn simulate(&mut self, game: &Game) -> usize {
+        playout(FastGame::from(game), self.rollout, &mut self.rng)
+    }
+
+    // cerca la posizione attuale tra i discendenti della vecchia radice (fino a un giro
+    // completo di mosse) e ne tiene il sottoalbero; altrimenti riparte da zero
+    fn reuse(&mut self, game: &Game) {
+        let mut level = if self.tree.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        for _ in 0..=game.players() {
+            if let Some(&found) = level
+                .iter()
+                .find(|&&node| self.tree[node].hash == game.zobrist())
+            {
+                self.tree = self.subtree(found);
+                self.tree[0].coord = None;
+                return;
+            }
+            level = level
+                .iter()
+                .flat_map(|&node| self.tree[node].children.iter().copied())
+                .collect();
+        }
+        self.tree = vec![Node::new(game, None, usize::MAX)];
+    }
+
+    fn subtree(&self, root: usize) -> Vec<Node> {
+        let mut result = vec![self.tree[root].clone()];
+        let mut index = 0;
+        while index < result.len() {
+            let children = std::mem::take(&mut result[index].children);
+            for child in children {
+                let next = result.len();
+                result[index].children.push(next);
+                result.push(self.tree[child].clone());
+            }
+            index += 1;
+        }
+        result
+    }
+}
+
+impl Node {
+    fn new(game: &Game, coord: Option<Coord>, player: usize) -> Self {
+        Self {
+            coord,
+            player,
+            hash: game.zobrist(),
+            children: vec![],
+            untried: game.legal_moves().collect(),
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+}
+
+impl Bot for MctsBot {
+    fn choose_move(&mut self, game: &Game) -> Coord {
+        self.search(game)
+    }
+}
+
+// gioca fino alla fine e restituisce il vincitore
+pub(crate) fn playout(mut game: FastGame, rollout: Rollout, rng: &mut StdRng) -> usize {
+    while !game.won() {
+        let explosive = match rollout {
+            Rollout::Heuristic if rng.gen_bool(0.5) => game.explosive_moves().choose(rng),
+            _ => None,
+        };
+        let coord = explosive.unwrap_or_else(|| {
+            let index = rng.gen_range(0..game.legal_count());
+            game.legal_moves().nth(index).unwrap()
+        });
+        game.add_atom(coord).unwrap();
+    }
+    game.turn()
+}
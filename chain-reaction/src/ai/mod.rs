@@ -0,0 +1,97 @@
+use std::time::Duration;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Instant;
+
+use crate::{Coord, Game};
+
+pub mod difficulty;
+pub mod eval;
+pub mod greedy;
+pub mod mcts;
+pub mod random;
+pub mod search;
+
+// un giocatore automatico: la partita passata non deve essere già vinta
+pub trait Bot {
+    fn choose_move(&mut self, game: &Game) -> Coord;
+}
+
+// limiti di una ricerca; se sono entrambi assenti la ricerca si ferma solo per profondità
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+pub(crate) struct Limit {
+    budget: Budget,
+    start: Option<Clock>,
+    pub(crate) nodes: u64,
+}
+
+impl Budget {
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            nodes: Some(nodes),
+            time: None,
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Self {
+            nodes: None,
+            time: Some(time),
+        }
+    }
+
+    pub(crate) fn start(self) -> Limit {
+        Limit {
+            budget: self,
+            start: self.time.map(|_| Clock::now()),
+            nodes: 0,
+        }
+    }
+}
+
+impl Limit {
+    pub(crate) fn exhausted(&self) -> bool {
+        self.budget.nodes.is_some_and(|nodes| self.nodes >= nodes)
+            || self
+                .budget
+                .time
+                .zip(self.start)
+                .is_some_and(|(time, start)| start.elapsed() >= time)
+    }
+}
+
+// `Instant::now` va in panico su wasm32-unknown-unknown, lì il tempo si legge dall'orologio di
+// JavaScript
+#[cfg(not(target_family = "wasm"))]
+#[derive(Copy, Clone)]
+struct Clock(Instant);
+
+#[cfg(target_family = "wasm")]
+#[derive(Copy, Clone)]
+struct Clock(f64);
+
+#[cfg(not(target_family = "wasm"))]
+impl Clock {
+    fn now() -> Self {
+        Self(Instant::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl Clock {
+    fn now() -> Self {
+        Self(js_sys::Date::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((js_sys::Date::now() - self.0).max(0.0) / 1000.0)
+    }
+}
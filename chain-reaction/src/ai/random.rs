@@ -0,0 +1,24 @@
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+
+use crate::{ai::Bot, Coord, Game};
+
+#[derive(Clone, Debug)]
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Bot for RandomBot {
+    fn choose_move(&mut self, game: &Game) -> Coord {
+        game.legal_moves()
+            .choose(&mut self.rng)
+            .expect("no legal moves, the game is already won")
+    }
+}
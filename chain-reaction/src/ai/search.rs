@@ -0,0 +1,348 @@
+use std::sync::Arc;
+
+use crate::{
+    ai::{
+        eval::{Evaluator, Material},
+        Bot, Budget, Limit,
+    },
+    tablebase::{Tablebase, Value},
+    transposition::{Bound, TranspositionTable},
+    Coord, Game,
+};
+
+// punteggio di una vittoria; le vittorie più vicine valgono un po' di più
+pub const WIN: i32 = 1_000_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // tutti gli avversari giocano contro chi cerca: si riduce a due giocatori e si può potare
+    Paranoid,
+    // ogni giocatore massimizza il proprio punteggio
+    MaxN,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Coord,
+    // dal punto di vista di chi muove nella posizione cercata
+    pub score: i32,
+    // ultima profondità completata
+    pub depth: u8,
+    pub nodes: u64,
+}
+
+// ricerca ad approfondimento iterativo: ad ogni iterazione si scende di un livello in più
+// finché non si esaurisce il budget o si arriva a `max_depth`; un'iterazione interrotta viene
+// scartata. Senza limiti nel budget la ricerca si ferma dopo `DEFAULT_NODES` nodi
+#[derive(Clone, Debug)]
+pub struct SearchBot<E = Material> {
+    pub strategy: Strategy,
+    pub budget: Budget,
+    pub max_depth: u8,
+    pub table: TranspositionTable,
+    pub evaluator: E,
+    // se la posizione è nella tabella la mossa si legge da lì senza cercare
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+const DEFAULT_NODES: u64 = 20_000;
+
+impl SearchBot {
+    pub fn new(strategy: Strategy, budget: Budget) -> Self {
+        Self::with_evaluator(strategy, budget, Material)
+    }
+}
+
+impl<E: Evaluator> SearchBot<E> {
+    pub fn with_evaluator(strategy: Strategy, budget: Budget, evaluator: E) -> Self {
+        Self {
+            strategy,
+            budget,
+            max_depth: 64,
+            table: TranspositionTable::with_memory(4 << 20),
+            evaluator,
+            tablebase: None,
+        }
+    }
+
+    pub fn search(&mut self, game: &Game) -> SearchResult {
+        if let Some(probe) = self.tablebase.as_ref().and_then(|table| table.probe(game)) {
+            let score = match probe.value {
+                Value::Win(distance) => WIN - distance as i32,
+                Value::Loss(distance) => -WIN + distance as i32,
+            };
+            return SearchResult {
+                best_move: probe.best_move,
+                score,
+                depth: 0,
+                nodes: 0,
+            };
+        }
+        let mut game = game.snapshot();
+        let mut moves: Vec<_> = game.legal_moves().collect();
+        assert!(!moves.is_empty(), "no legal moves, the game is already won");
+        game.order_by_impact(&mut moves);
+        let budget = if self.budget == Budget::default() {
+            Budget::nodes(DEFAULT_NODES)
+        } else {
+            self.budget
+        };
+        self.search_moves(&mut game, &moves, budget)
+    }
+
+    // la ricerca ristretta alle mosse date, che devono essere legali; qui un budget senza
+    // limiti non viene sostituito, serve a chi limita già la profondità con `max_depth`
+    pub(crate) fn search_moves(
+        &mut self,
+        game: &mut Game,
+        moves: &[Coord],
+        budget: Budget,
+    ) -> SearchResult {
+        let mut search = Search {
+            limit: budget.start(),
+            table: &mut self.table,
+            evaluator: &self.evaluator,
+            root: game.turn(),
+            restricted: moves.len() < game.legal_moves().count(),
+        };
+        search.table.new_search();
+        let mut result = SearchResult {
+            best_move: moves[0],
+            score: 0,
+            depth: 0,
+            nodes: 0,
+        };
+        for depth in 1..=self.max_depth {
+            let completed = match self.strategy {
+                Strategy::Paranoid => search.paranoid_root(game, moves, depth),
+                Strategy::MaxN => search.maxn_root(game, moves, depth),
+            };
+            let Some((best_move, score)) = completed else {
+                break;
+            };
+            result = SearchResult {
+                best_move,
+                score,
+                depth,
+                nodes: search.limit.nodes,
+            };
+            // inutile cercare più a fondo se il risultato è già deciso
+            if score.abs() > WIN - 1000 {
+                break;
+            }
+        }
+        result.nodes = search.limit.nodes;
+        result
+    }
+}
+
+impl<E: Evaluator> Bot for SearchBot<E> {
+    fn choose_move(&mut self, game: &Game) -> Coord {
+        self.search(game).best_move
+    }
+}
+
+struct Search<'a, E> {
+    limit: Limit,
+    table: &'a mut TranspositionTable,
+    evaluator: &'a E,
+    root: usize,
+    // la radice è cercata solo su alcune mosse, il suo punteggio non vale per la posizione
+    restricted: bool,
+}
+
+impl<E: Evaluator> Search<'_, E> {
+    fn paranoid_root(
+        &mut self,
+        game: &mut Game,
+        moves: &[Coord],
+        depth: u8,
+    ) -> Option<(Coord, i32)> {
+        let key = self.key(game);
+        let mut moves = moves.to_vec();
+        order(
+            &mut moves,
+            self.table.probe(key).and_then(|entry| entry.best_move),
+        );
+        let (mut alpha, beta) = (-WIN - 1, WIN + 1);
+        let mut best = moves[0];
+        for coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            self.limit.nodes += 1;
+            let score = self.paranoid(game, depth - 1, 1, alpha, beta);
+            game.unmake_move(undo);
+            let score = score?;
+            if score > alpha {
+                alpha = score;
+                best = coord;
+            }
+        }
+        if !self.restricted {
+            self.table
+                .store(key, depth, alpha, Bound::Exact, Some(best));
+        }
+        Some((best, alpha))
+    }
+
+    // restituisce `None` se il budget finisce durante la ricerca
+    fn paranoid(
+        &mut self,
+        game: &mut Game,
+        depth: u8,
+        ply: u8,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> Option<i32> {
+        if game.won() {
+            return Some(terminal(game, self.root, ply));
+        }
+        if depth == 0 {
+            return Some(self.evaluator.evaluate(game, self.root));
+        }
+        if self.limit.exhausted() {
+            return None;
+        }
+        let key = self.key(game);
+        let entry = self.table.probe(key);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            let score = from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return Some(score),
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return Some(score);
+            }
+        }
+        let mut moves: Vec<_> = game.legal_moves().collect();
+        // vicino alle foglie misurare le mosse costa più di quanto fa risparmiare
+        if depth > 1 {
+            game.order_by_impact(&mut moves);
+        }
+        order(&mut moves, entry.and_then(|entry| entry.best_move));
+        let maximizing = game.turn() == self.root;
+        let (original_alpha, original_beta) = (alpha, beta);
+        let mut best_score = if maximizing { -WIN - 1 } else { WIN + 1 };
+        let mut best_move = None;
+        for coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            self.limit.nodes += 1;
+            let score = self.paranoid(game, depth - 1, ply + 1, alpha, beta);
+            game.unmake_move(undo);
+            let score = score?;
+            if (maximizing && score > best_score) || (!maximizing && score < best_score) {
+                best_score = score;
+                best_move = Some(coord);
+            }
+            if maximizing {
+                alpha = alpha.max(score);
+            } else {
+                beta = beta.min(score);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= original_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table
+            .store(key, depth, to_table(best_score, ply), bound, best_move);
+        Some(best_score)
+    }
+
+    fn maxn_root(&mut self, game: &mut Game, moves: &[Coord], depth: u8) -> Option<(Coord, i32)> {
+        let mut best = (moves[0], i32::MIN);
+        for &coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            self.limit.nodes += 1;
+            let scores = self.maxn(game, depth - 1, 1);
+            game.unmake_move(undo);
+            let score = scores?[self.root];
+            if score > best.1 {
+                best = (coord, score);
+            }
+        }
+        Some(best)
+    }
+
+    // un punteggio per ogni giocatore, chi muove sceglie il figlio migliore per sé
+    fn maxn(&mut self, game: &mut Game, depth: u8, ply: u8) -> Option<[i32; 8]> {
+        let mut scores = [0; 8];
+        if game.won() {
+            for (player, score) in scores.iter_mut().enumerate().take(game.players()) {
+                *score = terminal(game, player, ply);
+            }
+            return Some(scores);
+        }
+        if depth == 0 {
+            for (player, score) in scores.iter_mut().enumerate().take(game.players()) {
+                *score = self.evaluator.evaluate(game, player);
+            }
+            return Some(scores);
+        }
+        if self.limit.exhausted() {
+            return None;
+        }
+        let player = game.turn();
+        let moves: Vec<_> = game.legal_moves().collect();
+        let mut best: Option<[i32; 8]> = None;
+        for coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            self.limit.nodes += 1;
+            let child = self.maxn(game, depth - 1, ply + 1);
+            game.unmake_move(undo);
+            let child = child?;
+            if best.is_none_or(|best| child[player] > best[player]) {
+                best = Some(child);
+            }
+        }
+        best
+    }
+
+    // la stessa posizione ha valori diversi a seconda di chi cerca
+    fn key(&self, game: &Game) -> u64 {
+        game.zobrist() ^ (self.root as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    }
+}
+
+// mette per prima la mossa migliore trovata in precedenza
+fn order(moves: &mut [Coord], best: Option<Coord>) {
+    if let Some(index) = best.and_then(|best| moves.iter().position(|&coord| coord == best)) {
+        moves[..=index].rotate_right(1);
+    }
+}
+
+fn terminal(game: &Game, player: usize, ply: u8) -> i32 {
+    if game.turn() == player {
+        WIN - ply as i32
+    } else {
+        -WIN + ply as i32
+    }
+}
+
+// nella tabella le vittorie sono salvate come distanza dalla posizione, non dalla radice
+fn to_table(score: i32, ply: u8) -> i32 {
+    if score > WIN - 1000 {
+        score + ply as i32
+    } else if score < -WIN + 1000 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i32, ply: u8) -> i32 {
+    if score > WIN - 1000 {
+        score - ply as i32
+    } else if score < -WIN + 1000 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
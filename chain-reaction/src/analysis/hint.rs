@@ -0,0 +1,60 @@
+use crate::{
+    ai::{
+        eval::{Evaluator, Weighted},
+        search::{SearchBot, Strategy, WIN},
+        Budget,
+    },
+    analysis::capture_threats,
+    Coord, Game,
+};
+
+// la mossa consigliata e perché conviene giocarla
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Hint {
+    pub best_move: Coord,
+    // punteggio della ricerca dal punto di vista di chi muove
+    pub score: i32,
+    pub wins: bool,
+    // celle avversarie conquistate dalla mossa
+    pub captured: Vec<Coord>,
+    // round di esplosioni innescati, 0 se la mossa non fa esplodere niente
+    pub chain: usize,
+    // celle critiche avversarie che minacciavano di catturare qualcosa e non lo fanno più
+    pub neutralised: Vec<Coord>,
+}
+
+// `None` se la partita è già vinta; con il budget di default la ricerca resta comunque finita,
+// così un pulsante "suggerimento" non resta mai bloccato
+pub fn hint(game: &Game, budget: Budget) -> Option<Hint> {
+    hint_with(game, &mut hinter(budget))
+}
+
+// il bot usato da `hint`; chi chiede molti suggerimenti può crearlo una volta sola e passarlo a
+// `hint_with`, così la tabella delle trasposizioni non viene riallocata ogni volta
+pub fn hinter(budget: Budget) -> SearchBot<Weighted> {
+    SearchBot::with_evaluator(Strategy::Paranoid, budget, Weighted::standard())
+}
+
+pub fn hint_with<E: Evaluator>(game: &Game, bot: &mut SearchBot<E>) -> Option<Hint> {
+    if game.won() {
+        return None;
+    }
+    let result = bot.search(game);
+    let preview = game.preview(result.best_move).unwrap();
+    let before = capture_threats(game, game.turn());
+    let after = capture_threats(&preview.game, game.turn());
+    let neutralised = before
+        .into_iter()
+        .filter(|(coord, _)| after.iter().all(|(other, _)| other != coord))
+        .map(|(coord, _)| coord)
+        .collect();
+    Some(Hint {
+        best_move: result.best_move,
+        score: result.score,
+        wins: preview.game.won() || result.score > WIN - 1000,
+        captured: preview.captures.cells,
+        chain: preview.explosions.len(),
+        neutralised,
+    })
+}
@@ -0,0 +1,44 @@
+use crate::{Coord, Game};
+
+pub mod hint;
+pub mod moves;
+pub mod odds;
+pub mod report;
+pub mod threats;
+
+// per ogni cella critica degli avversari di `player` le celle di `player` che farebbe
+// catturare se venisse giocata; le celle che non catturano niente non sono minacce
+pub(crate) fn capture_threats(game: &Game, player: usize) -> Vec<(Coord, Vec<Coord>)> {
+    let mut threats = vec![];
+    if game.won() {
+        return threats;
+    }
+    let opponents: Vec<_> = (0..game.players())
+        .filter(|&p| p != player && game.players[p].atoms > 0)
+        .collect();
+    for opponent in opponents {
+        let mut game = game.with_turn(opponent);
+        let moves: Vec<_> = game
+            .cells()
+            .filter(|&coord| game.is_critical(coord) && game.get(coord).player() == opponent)
+            .collect();
+        for coord in moves {
+            let before = game.board.clone();
+            let undo = game.make_move(coord).unwrap();
+            let captured: Vec<_> = before
+                .iter()
+                .zip(&game.board)
+                .enumerate()
+                .filter(|(_, (old, new))| {
+                    old.atoms() != 0 && old.player() == player && new.player() == opponent
+                })
+                .map(|(i, _)| (i / game.width, i % game.width))
+                .collect();
+            game.unmake_move(undo);
+            if !captured.is_empty() {
+                threats.push((coord, captured));
+            }
+        }
+    }
+    threats
+}
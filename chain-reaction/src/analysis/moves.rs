@@ -0,0 +1,94 @@
+use std::cmp::Reverse;
+
+use crate::{Coord, Game};
+
+// cosa succede giocando una mossa, senza toccare la partita
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Impact {
+    pub coord: Coord,
+    // round di esplosioni, 0 se la mossa non fa esplodere niente
+    pub rounds: usize,
+    // celle esplose almeno una volta
+    pub exploded: usize,
+    // atomi catturati a ciascun giocatore, indicizzati per giocatore
+    pub captured: Vec<u16>,
+    pub eliminated: Vec<usize>,
+    pub wins: bool,
+}
+
+// la stessa misura senza allocazioni, per ordinare le mosse durante la ricerca
+#[derive(Copy, Clone, Debug)]
+struct Measure {
+    rounds: usize,
+    exploded: usize,
+    captured: [u16; 8],
+    alive: [bool; 8],
+    wins: bool,
+}
+
+// prima le vittorie, poi le eliminazioni, gli atomi catturati e la lunghezza della reazione
+type Rank = (bool, usize, u16, usize, usize);
+
+impl Measure {
+    fn rank(&self) -> Rank {
+        (
+            self.wins,
+            self.alive.iter().filter(|&&alive| !alive).count(),
+            self.captured.iter().sum(),
+            self.rounds,
+            self.exploded,
+        )
+    }
+}
+
+impl Game {
+    // tutte le mosse legali dalla più incisiva alla meno; a parità l'ordine è quello di
+    // `legal_moves`
+    pub fn impacts(&self) -> Vec<Impact> {
+        let mut game = self.snapshot();
+        let mut measures: Vec<_> = self
+            .legal_moves()
+            .map(|coord| (coord, game.measure(coord)))
+            .collect();
+        measures.sort_by_key(|(_, measure)| Reverse(measure.rank()));
+        measures
+            .into_iter()
+            .map(|(coord, measure)| Impact {
+                coord,
+                rounds: measure.rounds,
+                exploded: measure.exploded,
+                captured: measure.captured[..self.players()].to_vec(),
+                eliminated: (0..self.players()).filter(|&p| !measure.alive[p]).collect(),
+                wins: measure.wins,
+            })
+            .collect()
+    }
+
+    // ordina le mosse per impatto decrescente, misurando ciascuna una volta sola
+    pub(crate) fn order_by_impact(&mut self, moves: &mut [Coord]) {
+        moves.sort_by_cached_key(|&coord| Reverse(self.measure(coord).rank()));
+    }
+
+    fn measure(&mut self, coord: Coord) -> Measure {
+        let mut before = [0; 8];
+        for (atoms, player) in before.iter_mut().zip(&self.players) {
+            *atoms = player.atoms;
+        }
+        let undo = self.make_move(coord).unwrap();
+        let (rounds, exploded) = self.last_cascade();
+        let mut measure = Measure {
+            rounds,
+            exploded,
+            captured: [0; 8],
+            alive: [true; 8],
+            wins: self.won,
+        };
+        for (p, player) in self.players.iter().enumerate() {
+            measure.captured[p] = before[p].saturating_sub(player.atoms);
+            measure.alive[p] = before[p] == 0 || player.atoms > 0;
+        }
+        self.unmake_move(undo);
+        measure
+    }
+}
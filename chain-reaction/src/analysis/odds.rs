@@ -0,0 +1,118 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    ai::mcts::{playout, Rollout},
+    bitboard::FastGame,
+    Coord, Error, Game,
+};
+
+// quantile della normale per gli intervalli di confidenza al 95%
+const Z: f64 = 1.96;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Odds {
+    pub wins: u32,
+    pub probability: f64,
+    // intervallo di confidenza di Wilson, che resta dentro [0, 1] anche con poche partite
+    pub low: f64,
+    pub high: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Estimate {
+    pub playouts: u32,
+    // una voce per giocatore
+    pub players: Vec<Odds>,
+}
+
+// stima le probabilità di vittoria giocando `playouts` partite fino alla fine; a parità di seme
+// la stima è sempre la stessa
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Estimator {
+    pub playouts: u32,
+    pub rollout: Rollout,
+    pub seed: u64,
+}
+
+impl Estimator {
+    pub fn new(playouts: u32, seed: u64) -> Self {
+        Self {
+            playouts,
+            rollout: Rollout::Random,
+            seed,
+        }
+    }
+
+    // se la partita è già vinta non serve giocare: la stima conta un'unica partita, vinta da chi
+    // ha vinto davvero, con intervalli ridotti a un punto perché l'esito è certo
+    pub fn estimate(&self, game: &Game) -> Estimate {
+        if game.won() {
+            let players = (0..game.players())
+                .map(|player| {
+                    let wins = (player == game.turn()) as u32;
+                    Odds {
+                        wins,
+                        probability: wins as f64,
+                        low: wins as f64,
+                        high: wins as f64,
+                    }
+                })
+                .collect();
+            return Estimate {
+                playouts: 1,
+                players,
+            };
+        }
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let start = FastGame::from(game);
+        let mut wins = vec![0; game.players()];
+        for _ in 0..self.playouts {
+            wins[playout(start, self.rollout, &mut rng)] += 1;
+        }
+        Estimate {
+            playouts: self.playouts,
+            players: wins
+                .into_iter()
+                .map(|wins| odds(wins, self.playouts))
+                .collect(),
+        }
+    }
+
+    // una stima per ogni posizione della partita, dalla prima all'ultima compresa; ogni
+    // posizione usa un seme diverso, derivato da quello dello stimatore
+    pub fn timeline(&self, start: &Game, moves: &[Coord]) -> Result<Vec<Estimate>, Error> {
+        let mut game = start.snapshot();
+        let mut estimator = *self;
+        let mut result = vec![estimator.estimate(&game)];
+        for &coord in moves {
+            game.add_atom(coord)?;
+            estimator.seed = estimator.seed.wrapping_add(1);
+            result.push(estimator.estimate(&game));
+        }
+        Ok(result)
+    }
+}
+
+fn odds(wins: u32, playouts: u32) -> Odds {
+    if playouts == 0 {
+        return Odds {
+            wins,
+            probability: 0.0,
+            low: 0.0,
+            high: 1.0,
+        };
+    }
+    let n = playouts as f64;
+    let p = wins as f64 / n;
+    let denominator = 1.0 + Z * Z / n;
+    let centre = (p + Z * Z / (2.0 * n)) / denominator;
+    let half = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+    Odds {
+        wins,
+        probability: p,
+        low: (centre - half).max(0.0),
+        high: (centre + half).min(1.0),
+    }
+}
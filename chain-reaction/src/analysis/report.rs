@@ -0,0 +1,227 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    ai::{
+        eval::{Evaluator, Material},
+        search::{SearchBot, Strategy, WIN},
+        Budget,
+    },
+    Coord, Error, Game,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Annotation {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+// la perdita massima rispetto alla mossa migliore, nelle unità del valutatore, per ciascuna
+// annotazione; oltre `mistake` è un errore grave
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    pub good: i32,
+    pub inaccuracy: i32,
+    pub mistake: i32,
+}
+
+impl Default for Thresholds {
+    // tarate su `Material`, dove ogni atomo catturato sposta il punteggio di 2
+    fn default() -> Self {
+        Self {
+            good: 2,
+            inaccuracy: 6,
+            mistake: 12,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MoveReport {
+    pub ply: usize,
+    pub player: usize,
+    pub coord: Coord,
+    pub best_move: Coord,
+    // punteggi dal punto di vista di chi muove, con la stessa profondità
+    pub best_score: i32,
+    pub score: i32,
+    pub annotation: Annotation,
+    // chi muove stava meglio e dopo la mossa sta peggio
+    pub turning_point: bool,
+    // c'era una vittoria forzata e la mossa giocata la lascia sfuggire
+    pub missed_win: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Report {
+    pub moves: Vec<MoveReport>,
+    pub winner: Option<usize>,
+}
+
+// rigioca una partita valutando ogni posizione con il bot dato
+#[derive(Clone, Debug)]
+pub struct Analyser<E = Material> {
+    pub bot: SearchBot<E>,
+    pub thresholds: Thresholds,
+}
+
+impl Analyser {
+    pub fn new(budget: Budget) -> Self {
+        Self::with_bot(SearchBot::new(Strategy::Paranoid, budget))
+    }
+}
+
+impl<E: Evaluator> Analyser<E> {
+    pub fn with_bot(bot: SearchBot<E>) -> Self {
+        Self {
+            bot,
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    // gioca `moves` a partire da `start` e si ferma alla prima mossa illegale. La tabella dei
+    // finali del bot viene ignorata: la mossa giocata e la migliore devono essere valutate
+    // dalla stessa ricerca. Anche la tabella delle trasposizioni riparte vuota, altrimenti le
+    // voci più profonde lasciate dalle analisi precedenti cambierebbero i punteggi
+    pub fn analyse(&mut self, start: &Game, moves: &[Coord]) -> Result<Report, Error> {
+        self.bot.table.clear();
+        let tablebase = self.bot.tablebase.take();
+        let report = self.replay(start, moves);
+        self.bot.tablebase = tablebase;
+        report
+    }
+
+    fn replay(&mut self, start: &Game, moves: &[Coord]) -> Result<Report, Error> {
+        let mut game = start.snapshot();
+        let mut report = Report {
+            moves: vec![],
+            winner: None,
+        };
+        for (ply, &coord) in moves.iter().enumerate() {
+            if !game.is_legal(coord) {
+                return Err(if game.won() {
+                    Error::GameWon
+                } else {
+                    Error::Occupied
+                });
+            }
+            let best = self.bot.search(&game);
+            let score = if coord == best.best_move {
+                best.score
+            } else {
+                // la mossa giocata cercata da sola fino alla profondità raggiunta dalla migliore
+                let max_depth = self.bot.max_depth;
+                self.bot.max_depth = best.depth.max(1);
+                let played = self
+                    .bot
+                    .search_moves(&mut game, &[coord], Budget::default());
+                self.bot.max_depth = max_depth;
+                played.score
+            };
+            let loss = (best.score - score).max(0);
+            let annotation = if loss == 0 {
+                Annotation::Best
+            } else if loss <= self.thresholds.good {
+                Annotation::Good
+            } else if loss <= self.thresholds.inaccuracy {
+                Annotation::Inaccuracy
+            } else if loss <= self.thresholds.mistake {
+                Annotation::Mistake
+            } else {
+                Annotation::Blunder
+            };
+            report.moves.push(MoveReport {
+                ply: ply + 1,
+                player: game.turn(),
+                coord,
+                best_move: best.best_move,
+                best_score: best.score,
+                score,
+                annotation,
+                turning_point: best.score > 0 && score < 0,
+                missed_win: best.score > WIN - 1000 && score <= WIN - 1000,
+            });
+            game.add_atom(coord)?;
+        }
+        report.winner = game.won().then(|| game.turn());
+        Ok(report)
+    }
+}
+
+impl Report {
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Annotation::Best => "best",
+            Annotation::Good => "good",
+            Annotation::Inaccuracy => "inaccuracy",
+            Annotation::Mistake => "mistake",
+            Annotation::Blunder => "blunder",
+        };
+        f.pad(name)
+    }
+}
+
+// una riga per mossa, con i giocatori indicati dalle stesse lettere di `Game::from_ascii`:
+//
+//   3. a (1, 1)  mistake        -4  best (0, 0) +8  turning point
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for report in &self.moves {
+            write!(
+                f,
+                "{:>3}. {} {:?}  {:<10} {:>10}",
+                report.ply,
+                letter(report.player),
+                report.coord,
+                report.annotation,
+                score(report.score)
+            )?;
+            if report.annotation != Annotation::Best {
+                write!(
+                    f,
+                    "  best {:?} {}",
+                    report.best_move,
+                    score(report.best_score)
+                )?;
+            }
+            if report.turning_point {
+                write!(f, "  turning point")?;
+            }
+            if report.missed_win {
+                write!(f, "  missed win")?;
+            }
+            writeln!(f)?;
+        }
+        match self.winner {
+            Some(winner) => write!(f, "winner: {}", letter(winner)),
+            None => write!(f, "unfinished"),
+        }
+    }
+}
+
+fn letter(player: usize) -> char {
+    (b'a' + player as u8) as char
+}
+
+// le vittorie forzate sono mostrate come distanza in mosse
+fn score(score: i32) -> String {
+    if score > WIN - 1000 {
+        format!("win in {}", WIN - score)
+    } else if score < -WIN + 1000 {
+        format!("loss in {}", WIN + score)
+    } else {
+        format!("{:+}", score)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{analysis::capture_threats, Coord, Game};
+
+// una cella critica e cosa succederebbe se il proprietario ci giocasse
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CriticalCell {
+    pub cell: Coord,
+    // celle nemiche conquistate dalla reazione
+    pub captures: Vec<Coord>,
+    // round di esplosioni
+    pub chain: usize,
+}
+
+// minacce e punti deboli di un giocatore, calcolati come se toccasse a lui e poi a ciascun
+// avversario
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlayerThreats {
+    pub critical: Vec<CriticalCell>,
+    // le celle perse se un avversario gioca la risposta che ne cattura di più
+    pub vulnerable: Vec<Coord>,
+    // la reazione più lunga che il giocatore può innescare con una mossa
+    pub max_chain: usize,
+}
+
+impl Game {
+    // una voce per giocatore; quelli eliminati, o tutti se la partita è vinta, non hanno né
+    // minacce né punti deboli
+    pub fn threats(&self) -> Vec<PlayerThreats> {
+        let mut result = vec![PlayerThreats::default(); self.players()];
+        if self.won {
+            return result;
+        }
+        for (player, entry) in result.iter_mut().enumerate() {
+            if self.players[player].atoms == 0 {
+                continue;
+            }
+            let game = self.with_turn(player);
+            entry.critical = self
+                .cells()
+                .filter(|&coord| self.is_critical(coord) && self.get(coord).player() == player)
+                .map(|cell| {
+                    let preview = game.preview(cell).unwrap();
+                    CriticalCell {
+                        cell,
+                        captures: preview.captures.cells,
+                        chain: preview.explosions.len(),
+                    }
+                })
+                .collect();
+            entry.max_chain = entry
+                .critical
+                .iter()
+                .map(|critical| critical.chain)
+                .max()
+                .unwrap_or(0);
+            entry.vulnerable = capture_threats(self, player)
+                .into_iter()
+                .map(|(_, captured)| captured)
+                .max_by_key(Vec::len)
+                .unwrap_or_default();
+        }
+        result
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{Cell, Coord, Error, Game};
+
+pub type SmallGame = ArrayGame<11, 6>;
+pub type LargeGame = ArrayGame<18, 10>;
+
+// partita con dimensioni fissate a tempo di compilazione: la scacchiera sta in un array e
+// l'intera partita è `Copy`, quindi clonare una posizione non alloca nulla
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ArrayGame<const H: usize, const W: usize> {
+    board: [[Cell; W]; H],
+    players: [u16; 8],
+    num_players: usize,
+    turn: usize,
+    atoms: u16,
+    won: bool,
+}
+
+impl<const H: usize, const W: usize> ArrayGame<H, W> {
+    const VALID_SIZE: () = assert!(
+        3 <= H && H <= 18 && 3 <= W && W <= 10,
+        "board size out of range"
+    );
+
+    pub fn new(players: usize) -> Option<Self> {
+        let () = Self::VALID_SIZE;
+        if !(2..=8).contains(&players) {
+            return None;
+        }
+        let mut board = [[Cell::default(); W]; H];
+        for (row, cells) in board.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                *cell = Cell::new((row, col), H, W);
+            }
+        }
+        Some(Self {
+            board,
+            players: [0; 8],
+            num_players: players,
+            turn: 0,
+            atoms: 0,
+            won: false,
+        })
+    }
+
+    pub fn from_game(game: &Game) -> Option<Self> {
+        if game.height != H || game.width != W {
+            return None;
+        }
+        let mut result = Self::new(game.players.len())?;
+        for (index, &cell) in game.board.iter().enumerate() {
+            result.board[index / W][index % W] = cell;
+        }
+        for (atoms, player) in result.players.iter_mut().zip(&game.players) {
+            *atoms = player.atoms;
+        }
+        result.turn = game.turn;
+        result.atoms = game.atoms;
+        result.won = game.won;
+        Some(result)
+    }
+
+    pub fn add_atom(&mut self, (row, col): Coord) -> Result<(), Error> {
+        if self.won {
+            return Err(Error::GameWon);
+        }
+        let cell = &mut self.board[row][col];
+        if cell.atoms() != 0 && cell.player() != self.turn {
+            return Err(Error::Occupied);
+        }
+        cell.set_player(self.turn);
+        cell.add_atom();
+        self.atoms += 1;
+        self.players[self.turn] += 1;
+        if cell.must_explode() {
+            self.explode((row, col));
+        }
+        self.next_turn();
+        Ok(())
+    }
+
+    // stessa reazione di `Game::explode`, ma a round: prima esplodono tutte le celle critiche,
+    // poi i vicini ricevono gli atomi
+    fn explode(&mut self, start: Coord) {
+        let mut exploded = [[false; W]; H];
+        let mut exploded_count_down = H * W;
+        let mut to_explode = [[false; W]; H];
+        to_explode[start.0][start.1] = true;
+        let mut pending = true;
+        while pending && exploded_count_down > 0 {
+            for ((cells, targets), done) in
+                self.board.iter_mut().zip(&to_explode).zip(&mut exploded)
+            {
+                for ((cell, &target), done) in cells.iter_mut().zip(targets).zip(done) {
+                    if target {
+                        cell.explode();
+                        if !*done {
+                            *done = true;
+                            exploded_count_down -= 1;
+                        }
+                    }
+                }
+            }
+            for (row, targets) in to_explode.iter().enumerate() {
+                for (col, &target) in targets.iter().enumerate() {
+                    if !target {
+                        continue;
+                    }
+                    if row > 0 {
+                        self.receive((row - 1, col));
+                    }
+                    if row < H - 1 {
+                        self.receive((row + 1, col));
+                    }
+                    if col > 0 {
+                        self.receive((row, col - 1));
+                    }
+                    if col < W - 1 {
+                        self.receive((row, col + 1));
+                    }
+                }
+            }
+            pending = false;
+            for (cells, targets) in self.board.iter().zip(&mut to_explode) {
+                for (cell, target) in cells.iter().zip(targets) {
+                    *target = cell.must_explode();
+                    pending |= *target;
+                }
+            }
+        }
+        if exploded_count_down == 0 {
+            self.won = true;
+        }
+    }
+
+    fn receive(&mut self, (row, col): Coord) {
+        let cell = &mut self.board[row][col];
+        if cell.atoms() != 0 && cell.player() != self.turn {
+            self.players[cell.player()] -= cell.atoms() as u16;
+            self.players[self.turn] += cell.atoms() as u16;
+        }
+        cell.set_player(self.turn);
+        cell.add_atom();
+    }
+
+    fn next_turn(&mut self) {
+        let old_turn = self.turn;
+        loop {
+            self.turn = (self.turn + 1) % self.num_players;
+            if self.atoms <= self.num_players as u16 || self.players[self.turn] > 0 {
+                break;
+            }
+        }
+        if old_turn == self.turn {
+            self.won = true;
+        }
+    }
+
+    pub fn is_legal(&self, (row, col): Coord) -> bool {
+        if self.won || row >= H || col >= W {
+            return false;
+        }
+        let cell = self.board[row][col];
+        cell.atoms() == 0 || cell.player() == self.turn
+    }
+
+    pub fn legal_moves(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..H)
+            .flat_map(|row| (0..W).map(move |col| (row, col)))
+            .filter(|&coord| self.is_legal(coord))
+    }
+
+    pub fn get(&self, (row, col): Coord) -> Cell {
+        self.board[row][col]
+    }
+
+    pub fn atoms(&self, player: usize) -> u16 {
+        self.players[player]
+    }
+
+    pub fn height(&self) -> usize {
+        H
+    }
+
+    pub fn width(&self) -> usize {
+        W
+    }
+
+    pub fn players(&self) -> usize {
+        self.num_players
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn won(&self) -> bool {
+        self.won
+    }
+}
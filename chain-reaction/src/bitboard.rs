@@ -0,0 +1,357 @@
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::{Cell, Coord, Error, Game};
+
+// 3 parole da 64 bit bastano per la scacchiera più grande (18x10 = 180 celle); la cella
+// (row, col) corrisponde al bit `row * width + col`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct Bitboard([u64; 3]);
+
+// motore alternativo pensato per simulare molte partite: gli atomi di ogni cella sono tenuti
+// in binario su 3 piani di bit, così un round di esplosioni si risolve con shift e maschere
+// su tutta la scacchiera contemporaneamente; le regole sono le stesse di `Game`
+#[derive(Copy, Clone, Debug)]
+pub struct FastGame {
+    height: usize,
+    width: usize,
+    full: Bitboard,
+    first_col: Bitboard,
+    last_col: Bitboard,
+    // capacità delle celle in binario (2, 3 o 4)
+    capacity: [Bitboard; 3],
+    // atomi delle celle in binario (fino a 7 durante una reazione)
+    atoms: [Bitboard; 3],
+    // proprietari delle sole celle non vuote
+    owners: [Bitboard; 8],
+    num_players: usize,
+    turn: usize,
+    total_atoms: u16,
+    won: bool,
+}
+
+impl Bitboard {
+    fn single(index: usize) -> Self {
+        let mut result = Self::default();
+        result.0[index / 64] |= 1 << (index % 64);
+        result
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == [0; 3]
+    }
+
+    fn count(&self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    // `n` è sempre minore di 64 (al massimo la larghezza della scacchiera)
+    fn shl(self, n: usize) -> Self {
+        let [a, b, c] = self.0;
+        Self([
+            a << n,
+            (b << n) | (a >> (64 - n)),
+            (c << n) | (b >> (64 - n)),
+        ])
+    }
+
+    fn shr(self, n: usize) -> Self {
+        let [a, b, c] = self.0;
+        Self([
+            (a >> n) | (b << (64 - n)),
+            (b >> n) | (c << (64 - n)),
+            c >> n,
+        ])
+    }
+
+    fn indices(self) -> impl Iterator<Item = usize> {
+        self.0.into_iter().enumerate().flat_map(|(w, mut word)| {
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(w * 64 + bit)
+            })
+        })
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] & rhs.0[0],
+            self.0[1] & rhs.0[1],
+            self.0[2] & rhs.0[2],
+        ])
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] | rhs.0[0],
+            self.0[1] | rhs.0[1],
+            self.0[2] | rhs.0[2],
+        ])
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] ^ rhs.0[0],
+            self.0[1] ^ rhs.0[1],
+            self.0[2] ^ rhs.0[2],
+        ])
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self([!self.0[0], !self.0[1], !self.0[2]])
+    }
+}
+
+// somma di un bit (dove `mask` è attivo) ai numeri a 3 bit
+fn increment(number: &mut [Bitboard; 3], mask: Bitboard) {
+    let mut carry = mask;
+    for plane in number.iter_mut() {
+        let next = *plane & carry;
+        *plane = *plane ^ carry;
+        carry = next;
+    }
+}
+
+// differenza tra numeri a 3 bit; restituisce anche dove il risultato sarebbe negativo
+fn subtract(a: [Bitboard; 3], b: [Bitboard; 3]) -> ([Bitboard; 3], Bitboard) {
+    let mut result = [Bitboard::default(); 3];
+    let mut borrow = Bitboard::default();
+    for i in 0..3 {
+        let diff = a[i] ^ b[i];
+        result[i] = diff ^ borrow;
+        borrow = (!a[i] & b[i]) | (!diff & borrow);
+    }
+    (result, borrow)
+}
+
+impl FastGame {
+    pub fn new(height: usize, width: usize, players: usize) -> Option<Self> {
+        Game::new(height, width, players).map(|game| Self::from(&game))
+    }
+
+    pub fn add_atom(&mut self, (row, col): Coord) -> Result<(), Error> {
+        if self.won {
+            return Err(Error::GameWon);
+        }
+        let index = row * self.width + col;
+        let cell = Bitboard::single(index);
+        if !(self.occupied() & cell).is_empty() && (self.owners[self.turn] & cell).is_empty() {
+            return Err(Error::Occupied);
+        }
+        self.owners[self.turn] = self.owners[self.turn] | cell;
+        increment(&mut self.atoms, cell);
+        self.total_atoms += 1;
+        self.explode();
+        self.next_turn();
+        Ok(())
+    }
+
+    // tutte le celle critiche di un round esplodono insieme: il risultato coincide con quello di
+    // `Game::explode`, che le fa esplodere una alla volta, perché una cella non può accumulare
+    // abbastanza atomi da esplodere due volte nello stesso round
+    fn explode(&mut self) {
+        let mut exploded = Bitboard::default();
+        let mut to_explode = self.critical();
+        while !to_explode.is_empty() && exploded != self.full {
+            exploded = exploded | to_explode;
+            let capacity = self.capacity.map(|plane| plane & to_explode);
+            self.atoms = subtract(self.atoms, capacity).0;
+            let neighbours = [
+                to_explode.shl(self.width) & self.full,
+                to_explode.shr(self.width),
+                (to_explode & !self.last_col).shl(1),
+                (to_explode & !self.first_col).shr(1),
+            ];
+            let mut hit = Bitboard::default();
+            for neighbour in neighbours {
+                increment(&mut self.atoms, neighbour);
+                hit = hit | neighbour;
+            }
+            let occupied = self.occupied();
+            for owner in self.owners.iter_mut() {
+                *owner = *owner & !hit & occupied;
+            }
+            self.owners[self.turn] = self.owners[self.turn] | (hit & occupied);
+            to_explode = self.critical();
+        }
+        if exploded == self.full {
+            self.won = true;
+        }
+    }
+
+    fn next_turn(&mut self) {
+        let old_turn = self.turn;
+        loop {
+            self.turn = (self.turn + 1) % self.num_players;
+            if self.total_atoms <= self.num_players as u16 || !self.owners[self.turn].is_empty() {
+                break;
+            }
+        }
+        if old_turn == self.turn {
+            self.won = true;
+        }
+    }
+
+    fn occupied(&self) -> Bitboard {
+        self.atoms[0] | self.atoms[1] | self.atoms[2]
+    }
+
+    fn critical(&self) -> Bitboard {
+        !subtract(self.atoms, self.capacity).1 & self.full
+    }
+
+    pub fn is_legal(&self, (row, col): Coord) -> bool {
+        !self.won
+            && row < self.height
+            && col < self.width
+            && self.legal().contains(row * self.width + col)
+    }
+
+    pub fn legal_moves(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        let legal = if self.won {
+            Bitboard::default()
+        } else {
+            self.legal()
+        };
+        legal
+            .indices()
+            .map(move |index| (index / width, index % width))
+    }
+
+    // le mosse che fanno esplodere subito una cella propria
+    pub fn explosive_moves(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        let one_less = subtract(
+            self.capacity,
+            [self.full, Bitboard::default(), Bitboard::default()],
+        )
+        .0;
+        let equal = (0..3).fold(self.full, |equal, i| equal & !(self.atoms[i] ^ one_less[i]));
+        let explosive = if self.won {
+            Bitboard::default()
+        } else {
+            equal & self.owners[self.turn]
+        };
+        explosive
+            .indices()
+            .map(move |index| (index / width, index % width))
+    }
+
+    pub fn legal_count(&self) -> usize {
+        if self.won {
+            0
+        } else {
+            self.legal().count() as usize
+        }
+    }
+
+    fn legal(&self) -> Bitboard {
+        (!self.occupied() | self.owners[self.turn]) & self.full
+    }
+
+    pub fn get(&self, (row, col): Coord) -> Cell {
+        let index = row * self.width + col;
+        let number = |planes: &[Bitboard; 3]| {
+            (0..3)
+                .filter(|&i| planes[i].contains(index))
+                .map(|i| 1 << i)
+                .sum::<u8>()
+        };
+        let player = (0..self.num_players)
+            .find(|&p| self.owners[p].contains(index))
+            .unwrap_or(0);
+        Cell::from(number(&self.atoms), player, number(&self.capacity))
+    }
+
+    pub fn atoms(&self, player: usize) -> u16 {
+        self.atoms
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| ((*plane & self.owners[player]).count() << i) as u16)
+            .sum()
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn players(&self) -> usize {
+        self.num_players
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn won(&self) -> bool {
+        self.won
+    }
+}
+
+impl From<&Game> for FastGame {
+    fn from(game: &Game) -> Self {
+        let mut result = Self {
+            height: game.height,
+            width: game.width,
+            full: Bitboard::default(),
+            first_col: Bitboard::default(),
+            last_col: Bitboard::default(),
+            capacity: [Bitboard::default(); 3],
+            atoms: [Bitboard::default(); 3],
+            owners: [Bitboard::default(); 8],
+            num_players: game.players.len(),
+            turn: game.turn,
+            total_atoms: game.atoms,
+            won: game.won,
+        };
+        for (index, cell) in game.board.iter().enumerate() {
+            let bit = Bitboard::single(index);
+            result.full = result.full | bit;
+            match index % game.width {
+                0 => result.first_col = result.first_col | bit,
+                col if col == game.width - 1 => result.last_col = result.last_col | bit,
+                _ => {}
+            }
+            for i in 0..3 {
+                if cell.max_atoms() & (1 << i) != 0 {
+                    result.capacity[i] = result.capacity[i] | bit;
+                }
+                if cell.atoms() & (1 << i) != 0 {
+                    result.atoms[i] = result.atoms[i] | bit;
+                }
+            }
+            if cell.atoms() != 0 {
+                result.owners[cell.player()] = result.owners[cell.player()] | bit;
+            }
+        }
+        result
+    }
+}
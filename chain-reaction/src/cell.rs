@@ -37,7 +37,6 @@ impl Cell {
         }
     }
 
-    #[cfg(test)]
     pub(crate) fn from(atoms: u8, player: usize, max_atoms: u8) -> Self {
         Self {
             value: ((player as u8) << 5) | ((max_atoms - 2) << 3) | atoms,
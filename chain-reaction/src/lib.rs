@@ -1,6 +1,7 @@
 use std::{
     collections::{HashSet, VecDeque},
     fmt::Display,
+    str::FromStr,
 };
 
 use cell::Cell;
@@ -9,9 +10,20 @@ use deepsize::DeepSizeOf;
 #[cfg(feature = "serde")]
 use serde::{ser::SerializeMap, Serialize};
 
+pub mod ai;
+pub mod analysis;
+pub mod array;
+pub mod bitboard;
 mod cell;
+pub mod perft;
+pub mod render;
+pub mod solver;
+pub mod symmetry;
+pub mod tablebase;
 #[cfg(test)]
 mod tests;
+pub mod transposition;
+mod zobrist;
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "deepsize", derive(DeepSizeOf))]
@@ -25,6 +37,9 @@ pub struct Game {
     atoms: u16,
     won: bool,
     history: Vec<History>,
+    journal: Vec<(usize, Cell)>,
+    hash: u64,
+    scratch: Scratch,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -41,6 +56,16 @@ pub enum Error {
     GameWon,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ParseError {
+    InvalidSize,
+    InvalidPlayers,
+    InvalidTurn,
+    InvalidCell(Coord),
+    Unstable(Coord),
+}
+
 #[derive(Clone, Debug)]
 pub struct Explosion {
     pub result: Vec<Cell>,
@@ -48,6 +73,22 @@ pub struct Explosion {
     width: usize,
 }
 
+#[derive(Clone, Debug)]
+pub struct Preview {
+    pub game: Game,
+    pub explosions: Vec<Explosion>,
+    pub captures: Captures,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Captures {
+    // atomi persi da ciascun giocatore, indicizzati per giocatore
+    pub atoms: Vec<u16>,
+    pub cells: Vec<Coord>,
+    pub eliminated: Vec<usize>,
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "deepsize", derive(DeepSizeOf))]
 struct History {
@@ -55,9 +96,32 @@ struct History {
     players: Vec<Player>,
     turn: usize,
     atoms: u16,
+    hash: u64,
 }
 
-type Coord = (usize, usize);
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "deepsize", derive(DeepSizeOf))]
+struct Scratch {
+    exploded: Vec<bool>,
+    to_explode: VecDeque<usize>,
+    // round e celle esplose nell'ultima reazione, per chi analizza le mosse senza fotogrammi
+    rounds: usize,
+    cells: usize,
+}
+
+// stato minimo per annullare una mossa fatta con `make_move`: le celle modificate restano nel
+// giornale della partita, qui c'è solo il punto da cui ripristinarle
+#[derive(Copy, Clone, Debug)]
+pub struct Undo {
+    journal: usize,
+    players: [u16; 8],
+    turn: usize,
+    atoms: u16,
+    won: bool,
+    hash: u64,
+}
+
+pub type Coord = (usize, usize);
 
 impl Game {
     pub fn new(height: usize, width: usize, players: usize) -> Option<Self> {
@@ -77,9 +141,61 @@ impl Game {
             atoms: 0,
             won: false,
             history: vec![],
+            journal: vec![],
+            scratch: Scratch::default(),
+            hash: zobrist::turn(0),
         })
     }
 
+    pub fn from_ascii(board: &str, players: usize, turn: usize) -> Result<Self, ParseError> {
+        let rows: Vec<Vec<&str>> = board
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(ParseError::InvalidSize);
+        }
+        if !(2..=8).contains(&players) {
+            return Err(ParseError::InvalidPlayers);
+        }
+        let mut game = Self::new(height, width, players).ok_or(ParseError::InvalidSize)?;
+        if turn >= players {
+            return Err(ParseError::InvalidTurn);
+        }
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, token) in cells.iter().enumerate() {
+                let (atoms, player) =
+                    parse_cell(token).ok_or(ParseError::InvalidCell((row, col)))?;
+                if player >= players {
+                    return Err(ParseError::InvalidCell((row, col)));
+                }
+                let max_atoms = game.get((row, col)).max_atoms();
+                // una cella con troppi atomi sarebbe già esplosa
+                if atoms >= max_atoms {
+                    return Err(ParseError::Unstable((row, col)));
+                }
+                game.board[row * width + col] = Cell::from(atoms, player, max_atoms);
+                game.atoms += atoms as u16;
+                game.players[player].atoms += atoms as u16;
+            }
+        }
+        game.turn = turn;
+        game.hash = game.compute_hash();
+        // come in `next_turn`, finché ci sono pochi atomi nessuno può essere stato eliminato
+        if game.atoms > game.num_players {
+            let alive = game.players.iter().filter(|p| p.atoms > 0).count();
+            if game.players[turn].atoms == 0 {
+                return Err(ParseError::InvalidTurn);
+            }
+            game.won = alive == 1;
+        }
+        Ok(game)
+    }
+
     pub fn small(players: usize) -> Self {
         Self::new(11, 6, players).unwrap()
     }
@@ -90,6 +206,7 @@ impl Game {
 
     fn next_turn(&mut self) {
         let old_turn = self.turn;
+        self.hash ^= zobrist::turn(old_turn);
         loop {
             self.turn = (self.turn + 1) % self.players.len();
             // se ci sono meno atomi del numero di giocatori significa che nessuno può essere stato
@@ -99,12 +216,142 @@ impl Game {
                 break;
             }
         }
+        self.hash ^= zobrist::turn(self.turn);
         if old_turn == self.turn {
             self.won = true;
         }
     }
 
-    pub fn add_atom(&mut self, (row, col): Coord) -> Result<Vec<Explosion>, Error> {
+    pub fn add_atom(&mut self, coord: Coord) -> Result<Vec<Explosion>, Error> {
+        let index = self.check_move(coord)?;
+        self.history.push(History {
+            board: self.board.clone(),
+            players: self.players.clone(),
+            turn: self.turn,
+            atoms: self.atoms,
+            hash: self.hash,
+        });
+        Ok(self.play(index, true, false))
+    }
+
+    // variante di `add_atom` per la ricerca: non salva la cronologia e non costruisce i
+    // fotogrammi delle esplosioni, ma annota le celle modificate per poter tornare indietro
+    // con `unmake_move`
+    pub fn make_move(&mut self, coord: Coord) -> Result<Undo, Error> {
+        let index = self.check_move(coord)?;
+        let mut players = [0; 8];
+        for (saved, player) in players.iter_mut().zip(&self.players) {
+            *saved = player.atoms;
+        }
+        let undo = Undo {
+            journal: self.journal.len(),
+            players,
+            turn: self.turn,
+            atoms: self.atoms,
+            won: self.won,
+            hash: self.hash,
+        };
+        self.play(index, false, true);
+        Ok(undo)
+    }
+
+    // le mosse vanno annullate in ordine inverso rispetto a come sono state fatte
+    pub fn unmake_move(&mut self, undo: Undo) {
+        while self.journal.len() > undo.journal {
+            let (index, cell) = self.journal.pop().unwrap();
+            self.board[index] = cell;
+        }
+        for (player, &atoms) in self.players.iter_mut().zip(&undo.players) {
+            player.atoms = atoms;
+        }
+        self.turn = undo.turn;
+        self.atoms = undo.atoms;
+        self.won = undo.won;
+        self.hash = undo.hash;
+    }
+
+    pub fn preview(&self, coord: Coord) -> Result<Preview, Error> {
+        let index = self.check_move(coord)?;
+        // niente cronologia: l'anteprima non si annulla, si butta
+        let mut game = self.snapshot();
+        let explosions = game.play(index, true, false);
+        let captures = self.captures(&game);
+        Ok(Preview {
+            game,
+            explosions,
+            captures,
+        })
+    }
+
+    // la stessa partita con un'altra disposizione degli atomi, anche a metà di una reazione
+    pub(crate) fn with_board(&self, board: Vec<Cell>) -> Self {
+        let mut game = self.snapshot();
+        game.board = board;
+        for player in game.players.iter_mut() {
+            player.atoms = 0;
+        }
+        for cell in game.board.iter().filter(|cell| cell.atoms() != 0) {
+            game.players[cell.player()].atoms += cell.atoms() as u16;
+        }
+        game.atoms = game.players.iter().map(|player| player.atoms).sum();
+        game.hash = game.compute_hash();
+        game
+    }
+
+    // la stessa posizione con un altro giocatore a muovere, con l'hash aggiornato di conseguenza
+    pub(crate) fn with_turn(&self, turn: usize) -> Self {
+        let mut game = self.snapshot();
+        game.hash ^= zobrist::turn(game.turn) ^ zobrist::turn(turn);
+        game.turn = turn;
+        game
+    }
+
+    // copia della posizione senza la cronologia, che è la parte costosa da clonare
+    pub(crate) fn snapshot(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            height: self.height,
+            width: self.width,
+            players: self.players.clone(),
+            num_players: self.num_players,
+            turn: self.turn,
+            atoms: self.atoms,
+            won: self.won,
+            history: vec![],
+            journal: vec![],
+            scratch: Scratch::default(),
+            hash: self.hash,
+        }
+    }
+
+    // gli atomi non vengono mai distrutti dalle esplosioni, quindi quelli persi da un giocatore
+    // sono esattamente quelli catturati da chi ha mosso
+    fn captures(&self, after: &Self) -> Captures {
+        let mover = self.turn;
+        Captures {
+            atoms: self
+                .players
+                .iter()
+                .zip(&after.players)
+                .map(|(before, after)| before.atoms.saturating_sub(after.atoms))
+                .collect(),
+            cells: self
+                .board
+                .iter()
+                .zip(&after.board)
+                .enumerate()
+                .filter(|(_, (before, after))| {
+                    before.atoms() != 0 && before.player() != mover && after.player() == mover
+                })
+                .map(|(i, _)| (i / self.width, i % self.width))
+                .collect(),
+            eliminated: (0..self.players.len())
+                .filter(|&p| self.players[p].atoms > 0 && after.players[p].atoms == 0)
+                .collect(),
+        }
+    }
+
+    fn check_move(&self, (row, col): Coord) -> Result<usize, Error> {
         if self.won {
             return Err(Error::GameWon);
         }
@@ -114,35 +361,51 @@ impl Game {
         if cell.atoms() != 0 && cell.player() != self.turn {
             return Err(Error::Occupied);
         }
-        self.history.push(History {
-            board: self.board.clone(),
-            players: self.players.clone(),
-            turn: self.turn,
-            atoms: self.atoms,
-        });
+        Ok(index)
+    }
+
+    fn play(&mut self, index: usize, frames: bool, record: bool) -> Vec<Explosion> {
+        if record {
+            self.journal.push((index, self.board[index]));
+        }
         let cell = &mut self.board[index];
+        self.hash ^= zobrist::cell(index, *cell);
         cell.set_player(self.turn);
         cell.add_atom();
+        self.hash ^= zobrist::cell(index, *cell);
         self.atoms += 1;
         self.players[self.turn].atoms += 1;
         let result = if cell.must_explode() {
-            self.explode(index)
+            self.explode(index, frames, record)
         } else {
+            self.scratch.rounds = 0;
+            self.scratch.cells = 0;
             vec![]
         };
         self.next_turn();
-        Ok(result)
+        result
     }
 
-    fn explode(&mut self, index: usize) -> Vec<Explosion> {
+    fn explode(&mut self, index: usize, frames: bool, record: bool) -> Vec<Explosion> {
         let mut result = vec![];
         if !self.board[index].must_explode() {
             return result;
         }
-        let mut exploded = vec![false; self.board.len()];
+        // i buffer vengono riusati tra una mossa e l'altra, così a regime la reazione a catena
+        // non alloca nulla (a parte i fotogrammi, se richiesti)
+        let Scratch {
+            mut exploded,
+            mut to_explode,
+            ..
+        } = std::mem::take(&mut self.scratch);
+        exploded.clear();
+        exploded.resize(self.board.len(), false);
+        to_explode.clear();
+        to_explode.push_back(index);
         let mut exploded_count_down = self.board.len();
-        let mut to_explode = VecDeque::from([index]);
+        let mut rounds = 0;
         while !to_explode.is_empty() && exploded_count_down > 0 {
+            rounds += 1;
             let mut round = HashSet::new();
             for _ in 0..to_explode.len() {
                 let index = to_explode.pop_front().unwrap();
@@ -151,12 +414,19 @@ impl Game {
                 if !cell.must_explode() {
                     continue;
                 }
-                round.insert((index / self.width, index % self.width));
+                if frames {
+                    round.insert((index / self.width, index % self.width));
+                }
                 if !exploded[index] {
                     exploded[index] = true;
                     exploded_count_down -= 1;
                 }
+                if record {
+                    self.journal.push((index, *cell));
+                }
+                self.hash ^= zobrist::cell(index, *cell);
                 cell.explode();
+                self.hash ^= zobrist::cell(index, *cell);
                 for next in [
                     index.wrapping_sub(self.width),
                     index + self.width,
@@ -173,32 +443,52 @@ impl Game {
                 ] {
                     if next < self.board.len() {
                         let next_cell = &mut self.board[next];
+                        if record {
+                            self.journal.push((next, *next_cell));
+                        }
                         if next_cell.atoms() != 0 && next_cell.player() != self.turn {
                             self.players[next_cell.player()].atoms -= next_cell.atoms() as u16;
                             self.players[self.turn].atoms += next_cell.atoms() as u16;
                         }
+                        self.hash ^= zobrist::cell(next, *next_cell);
                         next_cell.set_player(self.turn);
                         next_cell.add_atom();
+                        self.hash ^= zobrist::cell(next, *next_cell);
                         if next_cell.must_explode() {
                             to_explode.push_back(next);
                         }
                     }
                 }
             }
-            result.push(Explosion::new(self.board.clone(), round, self.width));
+            if frames {
+                result.push(Explosion::new(self.board.clone(), round, self.width));
+            }
         }
         if exploded_count_down == 0 {
             self.won = true;
         }
+        self.scratch = Scratch {
+            exploded,
+            to_explode,
+            rounds,
+            cells: self.board.len() - exploded_count_down,
+        };
         result
     }
 
+    // round di esplosioni e celle esplose almeno una volta nell'ultima mossa giocata; vale solo
+    // subito dopo la mossa, prima di annullarla o di giocarne un'altra
+    pub(crate) fn last_cascade(&self) -> (usize, usize) {
+        (self.scratch.rounds, self.scratch.cells)
+    }
+
     pub fn undo(&mut self) -> bool {
         if let Some(history) = self.history.pop() {
             self.board = history.board;
             self.players = history.players;
             self.turn = history.turn;
             self.atoms = history.atoms;
+            self.hash = history.hash;
             self.won = false;
             true
         } else {
@@ -206,10 +496,59 @@ impl Game {
         }
     }
 
+    pub fn is_legal(&self, (row, col): Coord) -> bool {
+        if self.won || row >= self.height || col >= self.width {
+            return false;
+        }
+        let cell = self.board[row * self.width + col];
+        cell.atoms() == 0 || cell.player() == self.turn
+    }
+
+    pub fn legal_moves(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height)
+            .flat_map(move |row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&coord| self.is_legal(coord))
+    }
+
+    pub fn zobrist(&self) -> u64 {
+        self.hash
+    }
+
+    fn compute_hash(&self) -> u64 {
+        self.board
+            .iter()
+            .enumerate()
+            .fold(zobrist::turn(self.turn), |hash, (i, &cell)| {
+                hash ^ zobrist::cell(i, cell)
+            })
+    }
+
     pub fn get(&self, (row, col): Coord) -> Cell {
         self.board[row * self.width + col]
     }
 
+    pub fn neighbours(&self, (row, col): Coord) -> impl Iterator<Item = Coord> {
+        let (height, width) = (self.height, self.width);
+        [
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ]
+        .into_iter()
+        .filter(move |&(row, col)| row < height && col < width)
+    }
+
+    pub fn is_critical(&self, coord: Coord) -> bool {
+        let cell = self.get(coord);
+        cell.atoms() != 0 && cell.atoms() == cell.max_atoms() - 1
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.board.len()).map(move |i| (i / width, i % width))
+    }
+
     pub fn height(&self) -> usize {
         self.height
     }
@@ -217,6 +556,18 @@ impl Game {
     pub fn width(&self) -> usize {
         self.width
     }
+
+    pub fn players(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn won(&self) -> bool {
+        self.won
+    }
 }
 
 impl Explosion {
@@ -229,6 +580,26 @@ impl Explosion {
     }
 }
 
+fn parse_cell(token: &str) -> Option<(u8, usize)> {
+    let digits = token
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(token.len());
+    let atoms: u8 = token[..digits].parse().ok()?;
+    let mut owner = token[digits..].chars();
+    match (atoms, owner.next(), owner.next()) {
+        (0, None, _) => Some((0, 0)),
+        (1.., Some(mark @ 'a'..='h'), None) => Some((atoms, (mark as u8 - b'a') as usize)),
+        _ => None,
+    }
+}
+
+// i buffer di lavoro non fanno parte della posizione, non serve copiarli
+impl Clone for Scratch {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
 impl Default for Game {
     fn default() -> Self {
         Self::small(2)
@@ -236,10 +607,24 @@ impl Default for Game {
 }
 
 impl Display for Game {
+    // con `{:#}` ogni cella non vuota è seguita dalla lettera del proprietario (`2a 0 1b`) e
+    // la scacchiera è preceduta da una riga con giocatori e turno (`players 3 turn b`), così il
+    // testo si rilegge con `str::parse` ottenendo la stessa partita
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut result = String::new();
+        if f.alternate() {
+            result.push_str(&format!(
+                "players {} turn {}\n",
+                self.players.len(),
+                (b'a' + self.turn as u8) as char
+            ));
+        }
         for (i, cell) in self.board.iter().enumerate() {
-            result.push_str(&format!("{} ", cell.atoms()));
+            result.push_str(&cell.atoms().to_string());
+            if f.alternate() && cell.atoms() != 0 {
+                result.push((b'a' + cell.player() as u8) as char);
+            }
+            result.push(' ');
             if i % self.width == self.width - 1 {
                 result.pop();
                 result.push('\n');
@@ -250,6 +635,40 @@ impl Display for Game {
     }
 }
 
+impl FromStr for Game {
+    type Err = ParseError;
+
+    // legge l'output di `{:#}`; senza la riga iniziale con giocatori e turno, comoda nei test,
+    // i giocatori sono dedotti dal proprietario più alto presente e il turno è del primo
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(header) = s.trim_start().strip_prefix("players") {
+            let (header, board) = header.split_once('\n').unwrap_or((header, ""));
+            let mut tokens = header.split_whitespace();
+            let players = tokens
+                .next()
+                .and_then(|players| players.parse().ok())
+                .ok_or(ParseError::InvalidPlayers)?;
+            let turn = match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some("turn"), Some(mark), None) => match mark.as_bytes() {
+                    &[mark @ b'a'..=b'h'] => (mark - b'a') as usize,
+                    _ => return Err(ParseError::InvalidTurn),
+                },
+                _ => return Err(ParseError::InvalidTurn),
+            };
+            return Self::from_ascii(board, players, turn);
+        }
+        let players = s
+            .split_whitespace()
+            .filter_map(parse_cell)
+            .filter(|&(atoms, _)| atoms != 0)
+            .map(|(_, player)| player + 1)
+            .max()
+            .unwrap_or(0)
+            .max(2);
+        Self::from_ascii(s, players, 0)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl Serialize for Game {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use crate::{array::ArrayGame, bitboard::FastGame, Coord, Game};
+
+// conteggi di riferimento dalla posizione iniziale: `counts[d]` è perft(d + 1). Sono stati
+// calcolati sia con `Game` che con `FastGame`: se una modifica alla cascata ne cambia uno, è
+// cambiato il regolamento
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Known {
+    pub height: usize,
+    pub width: usize,
+    pub players: usize,
+    pub counts: &'static [u64],
+}
+
+pub const KNOWN: &[Known] = &[
+    Known {
+        height: 3,
+        width: 3,
+        players: 2,
+        counts: &[
+            9, 72, 576, 4_016, 28_312, 175_900, 1_097_772, 6_045_216, 33_232_596,
+        ],
+    },
+    Known {
+        height: 4,
+        width: 4,
+        players: 2,
+        counts: &[16, 240, 3_600, 50_468, 709_020, 9_325_180, 122_810_508],
+    },
+    Known {
+        height: 5,
+        width: 5,
+        players: 3,
+        counts: &[25, 600, 13_800, 317_400, 6_994_568, 147_450_672],
+    },
+    Known {
+        height: 11,
+        width: 6,
+        players: 2,
+        counts: &[66, 4_290, 278_850, 17_849_918, 1_142_655_670],
+    },
+    Known {
+        height: 18,
+        width: 10,
+        players: 2,
+        counts: &[180, 32_220, 5_767_380, 1_026_623_720],
+    },
+];
+
+impl Game {
+    // il numero di sequenze di `depth` mosse legali; le partite che finiscono prima non
+    // contano, come gli scacco matto nel perft degli scacchi
+    pub fn perft(&self, depth: usize) -> u64 {
+        sequences(&mut self.snapshot(), depth)
+    }
+
+    // perft(depth - 1) dopo ciascuna mossa, utile per trovare dove due backend divergono
+    pub fn divide(&self, depth: usize) -> Vec<(Coord, u64)> {
+        assert!(depth > 0, "divide needs at least one move");
+        let mut game = self.snapshot();
+        game.legal_moves()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|coord| {
+                let undo = game.make_move(coord).unwrap();
+                let count = sequences(&mut game, depth - 1);
+                game.unmake_move(undo);
+                (coord, count)
+            })
+            .collect()
+    }
+
+    // le posizioni distinte, secondo l'hash, raggiunte dopo esattamente `depth` mosse
+    pub fn perft_positions(&self, depth: usize) -> usize {
+        let mut seen = HashSet::new();
+        positions(&mut self.snapshot(), depth, &mut seen);
+        seen.len()
+    }
+}
+
+fn sequences(game: &mut Game, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if game.won() {
+        return 0;
+    }
+    let moves: Vec<_> = game.legal_moves().collect();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .into_iter()
+        .map(|coord| {
+            let undo = game.make_move(coord).unwrap();
+            let count = sequences(game, depth - 1);
+            game.unmake_move(undo);
+            count
+        })
+        .sum()
+}
+
+fn positions(game: &mut Game, depth: usize, seen: &mut HashSet<u64>) {
+    if depth == 0 {
+        seen.insert(game.zobrist());
+        return;
+    }
+    if game.won() {
+        return;
+    }
+    for coord in game.legal_moves().collect::<Vec<_>>() {
+        let undo = game.make_move(coord).unwrap();
+        positions(game, depth - 1, seen);
+        game.unmake_move(undo);
+    }
+}
+
+impl FastGame {
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            1
+        } else if self.won() {
+            0
+        } else if depth == 1 {
+            self.legal_count() as u64
+        } else {
+            self.legal_moves()
+                .map(|coord| {
+                    let mut game = *self;
+                    game.add_atom(coord).unwrap();
+                    game.perft(depth - 1)
+                })
+                .sum()
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> ArrayGame<H, W> {
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            1
+        } else if self.won() {
+            0
+        } else {
+            self.legal_moves()
+                .map(|coord| {
+                    let mut game = *self;
+                    game.add_atom(coord).unwrap();
+                    game.perft(depth - 1)
+                })
+                .sum()
+        }
+    }
+}
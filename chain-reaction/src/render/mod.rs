@@ -0,0 +1,14 @@
+pub mod svg;
+pub mod terminal;
+
+// un colore per ciascuno degli 8 giocatori possibili, condiviso da tutti i renderer
+pub const PLAYER_COLORS: [(u8, u8, u8); 8] = [
+    (230, 57, 70),
+    (69, 123, 230),
+    (76, 175, 80),
+    (255, 193, 7),
+    (156, 39, 176),
+    (0, 188, 212),
+    (255, 112, 67),
+    (236, 236, 236),
+];
@@ -0,0 +1,132 @@
+use std::{collections::HashSet, f64::consts::PI, fmt::Write};
+
+use crate::{render::PLAYER_COLORS, Cell, Coord, Explosion, Game};
+
+const HEADER: usize = 24;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Svg {
+    pub cell_size: usize,
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render(&self, game: &Game) -> String {
+        self.render_board(&game.board, game.width, &HashSet::new(), None)
+    }
+
+    pub fn render_explosion(&self, explosion: &Explosion, round: usize) -> String {
+        self.render_board(
+            &explosion.result,
+            explosion.width,
+            &explosion.exploded,
+            Some(round),
+        )
+    }
+
+    // un'immagine per ogni round della reazione a catena, numerati da 1
+    pub fn render_cascade(&self, explosions: &[Explosion]) -> Vec<String> {
+        explosions
+            .iter()
+            .enumerate()
+            .map(|(i, explosion)| self.render_explosion(explosion, i + 1))
+            .collect()
+    }
+
+    fn render_board(
+        &self,
+        board: &[Cell],
+        width: usize,
+        exploded: &HashSet<Coord>,
+        round: Option<usize>,
+    ) -> String {
+        let size = self.cell_size;
+        let height = board.len() / width;
+        let top = if round.is_some() { HEADER } else { 0 };
+        let mut svg = String::new();
+        // la scrittura su una String non può fallire
+        write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width * size,
+            h = height * size + top,
+        )
+        .unwrap();
+        svg.push_str("<defs>");
+        for (player, (r, g, b)) in PLAYER_COLORS.iter().enumerate() {
+            write!(
+                svg,
+                r##"<radialGradient id="orb{}" cx="35%" cy="35%" r="65%"><stop offset="0%" stop-color="#ffffff"/><stop offset="100%" stop-color="#{:02x}{:02x}{:02x}"/></radialGradient>"##,
+                player, r, g, b
+            )
+            .unwrap();
+        }
+        svg.push_str("</defs>");
+        write!(
+            svg,
+            r##"<rect width="100%" height="100%" fill="#1e1e24"/>"##
+        )
+        .unwrap();
+        if let Some(round) = round {
+            write!(
+                svg,
+                r##"<text x="6" y="{}" font-family="sans-serif" font-size="14" fill="#ffffff">Round {}</text>"##,
+                HEADER - 7,
+                round
+            )
+            .unwrap();
+        }
+        for (i, cell) in board.iter().enumerate() {
+            let (row, col) = (i / width, i % width);
+            let (x, y) = (col * size, row * size + top);
+            let fill = if exploded.contains(&(row, col)) {
+                r##"fill="#ff9800" fill-opacity="0.35" stroke="#ff9800" stroke-width="2""##
+            } else {
+                r##"fill="none" stroke="#55555f" stroke-width="1""##
+            };
+            write!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                x, y, size, size, fill
+            )
+            .unwrap();
+            let radius = size as f64 * 0.14;
+            for (dx, dy) in orbs(cell.atoms(), size as f64 * 0.22) {
+                write!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="url(#orb{})"/>"#,
+                    x as f64 + size as f64 / 2.0 + dx,
+                    y as f64 + size as f64 / 2.0 + dy,
+                    radius,
+                    cell.player()
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+// posizioni degli atomi rispetto al centro della cella: uno al centro, gli altri disposti in cerchio
+fn orbs(atoms: u8, spread: f64) -> Vec<(f64, f64)> {
+    match atoms {
+        0 => vec![],
+        1 => vec![(0.0, 0.0)],
+        _ => (0..atoms)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / atoms as f64 - PI / 2.0;
+                (spread * angle.cos(), spread * angle.sin())
+            })
+            .collect(),
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self { cell_size: 40 }
+    }
+}
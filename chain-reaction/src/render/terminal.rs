@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use crate::{render::PLAYER_COLORS, Cell, Coord, Explosion, Game};
+
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Debug, Default)]
+pub struct Terminal {
+    pub capacity: bool,
+    pub critical: bool,
+    pub last_move: Option<Coord>,
+    pub exploded: HashSet<Coord>,
+}
+
+impl Terminal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // evidenzia la mossa appena giocata e tutte le celle esplose nella reazione a catena
+    pub fn after_move(mut self, coord: Coord, explosions: &[Explosion]) -> Self {
+        self.last_move = Some(coord);
+        self.exploded = explosions
+            .iter()
+            .flat_map(|explosion| explosion.exploded.iter().copied())
+            .collect();
+        self
+    }
+
+    pub fn render(&self, game: &Game) -> String {
+        self.render_board(&game.board, game.width, &self.exploded)
+    }
+
+    // un singolo fotogramma della reazione: sono evidenziate solo le celle esplose in quel round
+    pub fn render_explosion(&self, explosion: &Explosion) -> String {
+        self.render_board(&explosion.result, explosion.width, &explosion.exploded)
+    }
+
+    fn render_board(&self, board: &[Cell], width: usize, exploded: &HashSet<Coord>) -> String {
+        let mut result = String::new();
+        for (i, cell) in board.iter().enumerate() {
+            let coord = (i / width, i % width);
+            result.push_str(&self.render_cell(
+                *cell,
+                self.last_move == Some(coord),
+                exploded.contains(&coord),
+            ));
+            result.push(if i % width == width - 1 { '\n' } else { ' ' });
+        }
+        result.pop();
+        result
+    }
+
+    fn render_cell(&self, cell: Cell, last_move: bool, exploded: bool) -> String {
+        let mut codes = vec![];
+        if cell.atoms() == 0 {
+            // grigio tenue per le celle vuote
+            codes.push("2".to_string());
+        } else {
+            let (r, g, b) = PLAYER_COLORS[cell.player()];
+            codes.push(format!("38;2;{};{};{}", r, g, b));
+            if self.critical && cell.atoms() == cell.max_atoms() - 1 {
+                codes.push("1;4".to_string());
+            }
+        }
+        if exploded {
+            codes.push("48;5;238".to_string());
+        }
+        if last_move {
+            codes.push("7".to_string());
+        }
+        let text = if self.capacity {
+            format!("{}/{}", cell.atoms(), cell.max_atoms())
+        } else {
+            cell.atoms().to_string()
+        };
+        format!("\x1b[{}m{}{}", codes.join(";"), text, RESET)
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::{
+    ai::{Budget, Limit},
+    Coord, Game,
+};
+
+// risultato per chi deve muovere; nel chain reaction non esistono patte
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Outcome {
+    Win,
+    Loss,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Solution {
+    pub outcome: Outcome,
+    // tutte le mosse vincenti, oppure tutte le mosse se la posizione è persa
+    pub moves: Vec<Coord>,
+}
+
+// risolve esattamente le partite a due giocatori, ricordando il valore di ogni posizione già
+// vista nella sua forma canonica; il budget conta le posizioni risolte. La tabella viene
+// mantenuta tra una chiamata e l'altra
+#[derive(Clone, Debug, Default)]
+pub struct Solver {
+    pub budget: Budget,
+    memo: HashMap<u64, bool>,
+}
+
+impl Solver {
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            memo: HashMap::new(),
+        }
+    }
+
+    // posizioni risolte finora
+    pub fn positions(&self) -> usize {
+        self.memo.len()
+    }
+
+    // `None` se la partita non è a due giocatori, è già vinta o il budget non basta
+    pub fn solve(&mut self, game: &Game) -> Option<Solution> {
+        if game.players() != 2 || game.won() {
+            return None;
+        }
+        let mut game = game.snapshot();
+        let mut limit = self.budget.start();
+        let me = game.turn();
+        let mut moves = vec![];
+        for coord in game.legal_moves().collect::<Vec<_>>() {
+            let undo = game.make_move(coord).unwrap();
+            let wins = self.child(&mut game, me, &mut limit);
+            game.unmake_move(undo);
+            if wins? {
+                moves.push(coord);
+            }
+        }
+        let outcome = if moves.is_empty() {
+            moves = game.legal_moves().collect();
+            Outcome::Loss
+        } else {
+            Outcome::Win
+        };
+        Some(Solution { outcome, moves })
+    }
+
+    pub fn outcome(&mut self, game: &Game) -> Option<Outcome> {
+        if game.players() != 2 || game.won() {
+            return None;
+        }
+        let mut limit = self.budget.start();
+        let wins = self.wins(&mut game.snapshot(), &mut limit)?;
+        Some(if wins { Outcome::Win } else { Outcome::Loss })
+    }
+
+    // se chi muove vince
+    fn wins(&mut self, game: &mut Game, limit: &mut Limit) -> Option<bool> {
+        let key = game.canonical().0.zobrist();
+        if let Some(&wins) = self.memo.get(&key) {
+            return Some(wins);
+        }
+        if limit.exhausted() {
+            return None;
+        }
+        let me = game.turn();
+        let mut moves: Vec<_> = game.legal_moves().collect();
+        // le mosse che esplodono subito sono quelle che più spesso vincono
+        moves.sort_by_key(|&coord| !game.is_critical(coord));
+        let mut wins = false;
+        for coord in moves {
+            let undo = game.make_move(coord).unwrap();
+            let child = self.child(game, me, limit);
+            game.unmake_move(undo);
+            if child? {
+                wins = true;
+                break;
+            }
+        }
+        limit.nodes += 1;
+        self.memo.insert(key, wins);
+        Some(wins)
+    }
+
+    // se `me`, che ha appena mosso, vince
+    fn child(&mut self, game: &mut Game, me: usize, limit: &mut Limit) -> Option<bool> {
+        if game.won() {
+            Some(game.turn() == me)
+        } else {
+            let wins = self.wins(game, limit)?;
+            Some(wins == (game.turn() == me))
+        }
+    }
+}
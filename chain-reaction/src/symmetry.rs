@@ -0,0 +1,115 @@
+use crate::{Coord, Game};
+
+// le trasformazioni che lasciano invariata una scacchiera creata da `Game::new`; le ultime
+// quattro scambiano righe e colonne e hanno senso solo per le scacchiere quadrate
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Symmetry {
+    Identity,
+    FlipHorizontal,
+    FlipVertical,
+    Rotate180,
+    Transpose,
+    AntiTranspose,
+    Rotate90,
+    Rotate270,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Rotate180,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+        Symmetry::Rotate90,
+        Symmetry::Rotate270,
+    ];
+
+    pub fn available(height: usize, width: usize) -> &'static [Symmetry] {
+        if height == width {
+            &Self::ALL
+        } else {
+            &Self::ALL[..4]
+        }
+    }
+
+    // FlipHorizontal specchia le colonne, FlipVertical le righe, Rotate90 ruota in senso orario
+    pub fn apply(self, (row, col): Coord, height: usize, width: usize) -> Coord {
+        let (last_row, last_col) = (height - 1, width - 1);
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::FlipHorizontal => (row, last_col - col),
+            Symmetry::FlipVertical => (last_row - row, col),
+            Symmetry::Rotate180 => (last_row - row, last_col - col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (last_col - col, last_row - row),
+            Symmetry::Rotate90 => (col, last_row - row),
+            Symmetry::Rotate270 => (last_col - col, row),
+        }
+    }
+
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            symmetry => symmetry,
+        }
+    }
+}
+
+impl Game {
+    pub fn transform(&self, symmetry: Symmetry) -> Self {
+        assert!(
+            Symmetry::available(self.height, self.width).contains(&symmetry),
+            "{:?} is not a symmetry of a {}x{} board",
+            symmetry,
+            self.height,
+            self.width
+        );
+        let mut game = self.snapshot();
+        for (i, &cell) in self.board.iter().enumerate() {
+            let (row, col) =
+                symmetry.apply((i / self.width, i % self.width), self.height, self.width);
+            game.board[row * self.width + col] = cell;
+        }
+        game.hash = game.compute_hash();
+        game
+    }
+
+    // la forma canonica è l'immagine lessicograficamente minima tra tutte le simmetrie; la
+    // simmetria restituita porta le coordinate di questa partita in quelle della forma canonica
+    pub fn canonical(&self) -> (Self, Symmetry) {
+        let symmetry = self.canonical_symmetry();
+        (self.transform(symmetry), symmetry)
+    }
+
+    pub fn canonical_symmetry(&self) -> Symmetry {
+        let (height, width) = (self.height, self.width);
+        // il contenuto della cella `index` dell'immagine secondo `symmetry`, ignorando il
+        // proprietario rimasto nelle celle vuote
+        let image = |symmetry: Symmetry, index: usize| {
+            let (row, col) =
+                symmetry
+                    .inverse()
+                    .apply((index / width, index % width), height, width);
+            let cell = self.board[row * width + col];
+            if cell.atoms() == 0 {
+                (0, 0)
+            } else {
+                (cell.atoms(), cell.player() + 1)
+            }
+        };
+        let mut best = Symmetry::Identity;
+        for &symmetry in &Symmetry::available(height, width)[1..] {
+            if (0..self.board.len())
+                .map(|i| image(symmetry, i))
+                .lt((0..self.board.len()).map(|i| image(best, i)))
+            {
+                best = symmetry;
+            }
+        }
+        best
+    }
+}
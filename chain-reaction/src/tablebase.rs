@@ -0,0 +1,263 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Read, Write},
+};
+
+use crate::{Cell, Coord, Game};
+
+const MAGIC: &[u8; 4] = b"CRTB";
+const VERSION: u8 = 1;
+
+// valore per chi muove: la partita finisce dopo quel numero di mosse, contando anche quella di
+// chi muove; chi vince sceglie la strada più corta, chi perde quella più lunga
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Value {
+    Win(u16),
+    Loss(u16),
+}
+
+impl Value {
+    // il valore per chi ha giocato la mossa che porta in questa posizione
+    fn parent(self) -> Self {
+        match self {
+            Value::Win(distance) => Value::Loss(distance + 1),
+            Value::Loss(distance) => Value::Win(distance + 1),
+        }
+    }
+
+    fn rank(self) -> i32 {
+        match self {
+            Value::Win(distance) => i32::MAX - distance as i32,
+            Value::Loss(distance) => i32::MIN + distance as i32,
+        }
+    }
+
+    fn encode(self) -> i16 {
+        match self {
+            Value::Win(distance) => distance as i16,
+            Value::Loss(distance) => -(distance as i16),
+        }
+    }
+
+    fn decode(value: i16) -> Self {
+        if value > 0 {
+            Value::Win(value as u16)
+        } else {
+            Value::Loss(value.unsigned_abs())
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Probe {
+    pub value: Value,
+    pub best_move: Coord,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    // hash della forma canonica
+    key: u64,
+    value: i16,
+    // indice della cella nella forma canonica
+    best_move: u8,
+}
+
+// tutte le posizioni a due giocatori con al più `max_atoms` atomi su una scacchiera, risolte
+// esattamente; si salvano solo le forme canoniche, ordinate per hash
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tablebase {
+    height: usize,
+    width: usize,
+    max_atoms: u16,
+    entries: Vec<Entry>,
+}
+
+impl Tablebase {
+    // le posizioni in cui entrambi i giocatori hanno almeno una cella, con ciascuno dei due a
+    // muovere; per risolverle bisogna giocarle fino in fondo, quindi ha senso solo sulle
+    // scacchiere più piccole
+    pub fn generate(height: usize, width: usize, max_atoms: u16) -> Option<Self> {
+        let base = Game::new(height, width, 2)?;
+        let mut boards = vec![];
+        enumerate(&base, 0, max_atoms, &mut base.board.clone(), &mut boards);
+        let mut memo = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut entries = vec![];
+        for board in boards {
+            for turn in 0..2 {
+                let mut game = base.with_board(board.clone());
+                if game.players.iter().any(|player| player.atoms == 0) {
+                    break;
+                }
+                game.turn = turn;
+                game.hash = game.compute_hash();
+                let mut game = game.canonical().0;
+                if !seen.insert(game.zobrist()) {
+                    continue;
+                }
+                let (value, (row, col)) = best(&mut game, &mut memo);
+                entries.push(Entry {
+                    key: game.zobrist(),
+                    value: value.encode(),
+                    best_move: (row * width + col) as u8,
+                });
+            }
+        }
+        entries.sort_unstable_by_key(|entry| entry.key);
+        Some(Self {
+            height,
+            width,
+            max_atoms,
+            entries,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn max_atoms(&self) -> u16 {
+        self.max_atoms
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // `None` se la posizione non è nella tabella
+    pub fn probe(&self, game: &Game) -> Option<Probe> {
+        if game.players() != 2
+            || game.won()
+            || (game.height, game.width) != (self.height, self.width)
+            || game.atoms > self.max_atoms
+        {
+            return None;
+        }
+        let (canonical, symmetry) = game.canonical();
+        let index = self
+            .entries
+            .binary_search_by_key(&canonical.zobrist(), |entry| entry.key)
+            .ok()?;
+        let entry = self.entries[index];
+        let coord = (
+            entry.best_move as usize / self.width,
+            entry.best_move as usize % self.width,
+        );
+        Some(Probe {
+            value: Value::decode(entry.value),
+            best_move: symmetry.inverse().apply(coord, self.height, self.width),
+        })
+    }
+
+    // intestazione di 13 byte seguita da 11 byte per posizione, tutto little endian
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, self.height as u8, self.width as u8])?;
+        writer.write_all(&self.max_atoms.to_le_bytes())?;
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        for entry in &self.entries {
+            writer.write_all(&entry.key.to_le_bytes())?;
+            writer.write_all(&entry.value.to_le_bytes())?;
+            writer.write_all(&[entry.best_move])?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut header = [0; 13];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a tablebase file"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("unsupported tablebase version"));
+        }
+        let (height, width) = (header[5] as usize, header[6] as usize);
+        if Game::new(height, width, 2).is_none() {
+            return Err(invalid("invalid board size"));
+        }
+        let max_atoms = u16::from_le_bytes([header[7], header[8]]);
+        let len = u32::from_le_bytes([header[9], header[10], header[11], header[12]]);
+        let mut entries = Vec::with_capacity(len.min(1 << 20) as usize);
+        let mut bytes = [0; 11];
+        for _ in 0..len {
+            reader.read_exact(&mut bytes)?;
+            let entry = Entry {
+                key: u64::from_le_bytes(bytes[..8].try_into().unwrap()),
+                value: i16::from_le_bytes([bytes[8], bytes[9]]),
+                best_move: bytes[10],
+            };
+            if entry.value == 0 || entry.best_move as usize >= height * width {
+                return Err(invalid("corrupted tablebase entry"));
+            }
+            entries.push(entry);
+        }
+        if entries.windows(2).any(|pair| pair[0].key >= pair[1].key) {
+            return Err(invalid("tablebase entries are not sorted"));
+        }
+        Ok(Self {
+            height,
+            width,
+            max_atoms,
+            entries,
+        })
+    }
+}
+
+// tutte le scacchiere stabili con al più `left` atomi a partire dalla cella `index`
+fn enumerate(base: &Game, index: usize, left: u16, board: &mut [Cell], out: &mut Vec<Vec<Cell>>) {
+    if index == board.len() {
+        out.push(board.to_vec());
+        return;
+    }
+    let empty = base.board[index];
+    enumerate(base, index + 1, left, board, out);
+    let max_atoms = empty.max_atoms();
+    for atoms in 1..max_atoms.min(left as u8 + 1) {
+        for player in 0..2 {
+            board[index] = Cell::from(atoms, player, max_atoms);
+            enumerate(base, index + 1, left - atoms as u16, board, out);
+        }
+    }
+    board[index] = empty;
+}
+
+// il valore di una posizione non ancora vinta e la mossa che lo ottiene
+fn best(game: &mut Game, memo: &mut HashMap<u64, Value>) -> (Value, Coord) {
+    let mut best: Option<(Value, Coord)> = None;
+    for coord in game.legal_moves().collect::<Vec<_>>() {
+        let undo = game.make_move(coord).unwrap();
+        let value = if game.won() {
+            Value::Win(1)
+        } else {
+            value(game, memo).parent()
+        };
+        game.unmake_move(undo);
+        if best.is_none_or(|(best, _)| value.rank() > best.rank()) {
+            best = Some((value, coord));
+        }
+    }
+    best.unwrap()
+}
+
+fn value(game: &mut Game, memo: &mut HashMap<u64, Value>) -> Value {
+    let key = game.canonical().0.zobrist();
+    if let Some(&value) = memo.get(&key) {
+        return value;
+    }
+    let value = best(game, memo).0;
+    memo.insert(key, value);
+    value
+}
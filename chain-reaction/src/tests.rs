@@ -1,6 +1,67 @@
-use std::collections::HashSet;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell as StdCell,
+    collections::HashSet,
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::{Cell, Game};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    ai::{
+        difficulty::{Difficulty, DifficultyBot, Engine, Noisy, Profile},
+        eval::{Critical, Edges, Evaluator, Material, Threats, Unstable, Weighted},
+        greedy::GreedyBot,
+        mcts::{MctsBot, Rollout},
+        random::RandomBot,
+        search::{SearchBot, Strategy, WIN},
+        Bot, Budget,
+    },
+    analysis::{
+        self,
+        moves::Impact,
+        odds::Estimator,
+        report::{Analyser, Annotation},
+        threats::{CriticalCell, PlayerThreats},
+    },
+    array::{ArrayGame, LargeGame, SmallGame},
+    bitboard::FastGame,
+    perft::KNOWN,
+    render::{svg::Svg, terminal::Terminal},
+    solver::{Outcome, Solver},
+    symmetry::Symmetry,
+    tablebase::{Tablebase, Value},
+    transposition::{Bound, TranspositionTable},
+    zobrist, Cell, Game, ParseError,
+};
+
+// conta le allocazioni fatte dal thread corrente, così i test in parallelo non interferiscono
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: StdCell<usize> = const { StdCell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS
+            .try_with(|count| count.set(count.get() + 1))
+            .ok();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(StdCell::get)
+}
 
 #[test]
 fn max_atoms_cell() {
@@ -324,6 +385,1144 @@ fn elimination() {
     assert_eq!(game.turn, 0);
 }
 
+#[test]
+fn ascii() {
+    let mut game = Game::new(3, 4, 3).unwrap();
+    for coord in [(0, 0), (1, 1), (2, 3), (0, 1), (1, 1)] {
+        game.add_atom(coord).unwrap();
+    }
+    assert_eq!(
+        format!("{:#}", game),
+        "players 3 turn c\n1a 1a 0 0\n0 2b 0 0\n0 0 0 1c"
+    );
+    assert_eq!(game.to_string(), "1 1 0 0\n0 2 0 0\n0 0 0 1");
+
+    let parsed: Game = format!("{:#}", game).parse().unwrap();
+    assert_eq!(parsed.zobrist(), game.zobrist());
+    assert_eq!(parsed.board, game.board);
+    assert_eq!(parsed.atoms, game.atoms);
+    assert_eq!(parsed.turn, game.turn);
+    assert_eq!(
+        parsed.players.iter().map(|p| p.atoms).collect::<Vec<_>>(),
+        game.players.iter().map(|p| p.atoms).collect::<Vec<_>>()
+    );
+
+    let game: Game = r"
+        0 1b 0
+        2a 0 0
+        0 0 1a
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(game.get((0, 1)), Cell::from(1, 1, 3));
+    assert_eq!(game.get((1, 0)), Cell::from(2, 0, 3));
+    assert_eq!(game.players.len(), 2);
+    assert_eq!(game.atoms, 4);
+
+    assert_eq!(
+        "1a 0 0\n0 0".parse::<Game>().err(),
+        Some(ParseError::InvalidSize)
+    );
+    assert_eq!(
+        "0 0 0\n0 1x 0\n0 0 0".parse::<Game>().err(),
+        Some(ParseError::InvalidCell((1, 1)))
+    );
+    assert_eq!(
+        "2a 0 0\n0 0 0\n0 0 0".parse::<Game>().err(),
+        Some(ParseError::Unstable((0, 0)))
+    );
+    assert_eq!(
+        Game::from_ascii("1a 0 0\n0 0 0\n0 0 1c", 2, 0).err(),
+        Some(ParseError::InvalidCell((2, 2)))
+    );
+    assert!(Game::from_ascii("1a 2a 0\n0 0 0\n0 0 0", 2, 0).unwrap().won);
+
+    // l'intestazione conserva giocatori e turno anche quando non si deducono dalla scacchiera
+    let mut game = Game::new(3, 3, 3).unwrap();
+    game.add_atom((1, 1)).unwrap();
+    let text = format!("{:#}", game);
+    assert_eq!(text, "players 3 turn b\n0 0 0\n0 1a 0\n0 0 0");
+    let parsed: Game = text.parse().unwrap();
+    assert_eq!((parsed.players(), parsed.turn()), (3, 1));
+    assert_eq!(parsed.zobrist(), game.zobrist());
+    assert_eq!(format!("{:#}", parsed), text);
+    assert_eq!(
+        "players x turn a\n0 0 0\n0 0 0\n0 0 0"
+            .parse::<Game>()
+            .err(),
+        Some(ParseError::InvalidPlayers)
+    );
+    assert_eq!(
+        "players 2 turn c\n0 0 0\n0 0 0\n0 0 0"
+            .parse::<Game>()
+            .err(),
+        Some(ParseError::InvalidTurn)
+    );
+    assert_eq!(
+        "players 2 move a\n0 0 0\n0 0 0\n0 0 0"
+            .parse::<Game>()
+            .err(),
+        Some(ParseError::InvalidTurn)
+    );
+}
+
+#[test]
+fn legal_moves() {
+    let game: Game = "1a 0 0\n0 2b 0\n0 0 1b".parse().unwrap();
+    assert_eq!(game.legal_moves().count(), 7);
+    assert!(game.is_legal((0, 0)));
+    assert!(!game.is_legal((1, 1)));
+    assert!(!game.is_legal((3, 0)));
+    assert!(!game.is_legal((0, 3)));
+    for coord in game.legal_moves() {
+        assert!(game.clone().add_atom(coord).is_ok());
+    }
+
+    let game = Game::from_ascii("1a 0 0\n0 2b 0\n0 0 1b", 2, 1).unwrap();
+    assert_eq!(
+        game.legal_moves().collect::<Vec<_>>(),
+        vec![
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2)
+        ]
+    );
+
+    let game = Game::from_ascii("1a 2a 0\n0 0 0\n0 0 0", 2, 0).unwrap();
+    assert!(game.won());
+    assert_eq!(game.legal_moves().count(), 0);
+}
+
+#[test]
+fn preview() {
+    let game = Game::from_ascii("1a 1b 0\n2b 0 0\n0 0 0", 2, 0).unwrap();
+    let preview = game.preview((0, 0)).unwrap();
+    assert_eq!(game.get((0, 0)), Cell::from(1, 0, 2));
+    assert!(game.history.is_empty());
+    assert_eq!(
+        format!("{:#}", preview.game),
+        "players 2 turn a\n1a 2a 0\n0 1a 0\n1a 0 0"
+    );
+    assert!(preview.game.history.is_empty());
+    // una mossa tranquilla copia la scacchiera e i giocatori una volta sola, più il conteggio
+    // degli atomi catturati
+    let before = allocations();
+    let quiet = game.preview((2, 2)).unwrap();
+    assert_eq!(allocations() - before, 3);
+    assert!(quiet.explosions.is_empty());
+    assert_eq!(preview.explosions.len(), 2);
+    assert_eq!(preview.captures.atoms, vec![0, 3]);
+    assert_eq!(preview.captures.cells, vec![(0, 1), (1, 0)]);
+    assert_eq!(preview.captures.eliminated, vec![1]);
+    assert!(preview.game.won());
+
+    let mut played = game.clone();
+    let explosions = played.add_atom((0, 0)).unwrap();
+    assert_eq!(played.board, preview.game.board);
+    assert_eq!(
+        explosions
+            .into_iter()
+            .map(|e| e.exploded)
+            .collect::<Vec<_>>(),
+        preview
+            .explosions
+            .into_iter()
+            .map(|e| e.exploded)
+            .collect::<Vec<_>>()
+    );
+    assert!(game.preview((1, 0)).is_err());
+}
+
+#[test]
+fn make_unmake() {
+    let mut fast = Game::new(4, 5, 3).unwrap();
+    let mut slow = fast.clone();
+    let mut undos = vec![];
+    let mut positions = vec![];
+    let mut i = 0;
+    while !slow.won() {
+        let moves: Vec<_> = slow.legal_moves().collect();
+        let coord = moves[(i * 7 + 3) % moves.len()];
+        positions.push((fast.board.clone(), fast.turn, fast.atoms));
+        slow.add_atom(coord).unwrap();
+        undos.push(fast.make_move(coord).unwrap());
+        assert_eq!(fast.board, slow.board);
+        assert_eq!(fast.turn, slow.turn);
+        assert_eq!(fast.atoms, slow.atoms);
+        assert_eq!(fast.won, slow.won);
+        i += 1;
+    }
+    assert!(fast.make_move((0, 0)).is_err());
+    while let Some(undo) = undos.pop() {
+        fast.unmake_move(undo);
+        let (board, turn, atoms) = positions.pop().unwrap();
+        assert_eq!(fast.board, board);
+        assert_eq!(fast.turn, turn);
+        assert_eq!(fast.atoms, atoms);
+        assert!(!fast.won);
+    }
+    assert!(fast.journal.is_empty());
+    assert!(fast.players.iter().all(|p| p.atoms == 0));
+}
+
+#[test]
+fn zobrist() {
+    let mut game = Game::new(5, 4, 2).unwrap();
+    let start = game.zobrist();
+    assert_eq!(start, game.compute_hash());
+    let mut hashes = vec![start];
+    let mut undos = vec![];
+    let mut i = 0;
+    while !game.won() {
+        let moves: Vec<_> = game.legal_moves().collect();
+        let coord = moves[(i * 5 + 1) % moves.len()];
+        if i % 2 == 0 {
+            game.add_atom(coord).unwrap();
+        } else {
+            undos.push(game.make_move(coord).unwrap());
+        }
+        assert_eq!(game.zobrist(), game.compute_hash());
+        hashes.push(game.zobrist());
+        i += 1;
+    }
+    // con l'hash completo le posizioni di una partita sono tutte diverse
+    let distinct: HashSet<_> = hashes.iter().collect();
+    assert_eq!(distinct.len(), hashes.len());
+
+    // stessa posizione raggiunta con ordini diversi, turno diverso a parità di scacchiera
+    let mut a = Game::new(3, 3, 2).unwrap();
+    let mut b = a.clone();
+    for coord in [(0, 0), (2, 2), (1, 1), (0, 2)] {
+        a.add_atom(coord).unwrap();
+    }
+    for coord in [(1, 1), (0, 2), (0, 0), (2, 2)] {
+        b.add_atom(coord).unwrap();
+    }
+    assert_eq!(a.zobrist(), b.zobrist());
+    // la stessa scacchiera con l'altro giocatore a muovere
+    let parsed: Game = format!("{:#}", a)
+        .replace("turn a", "turn b")
+        .parse()
+        .unwrap();
+    assert_ne!(a.zobrist(), parsed.zobrist());
+    assert_eq!(
+        a.zobrist(),
+        parsed.zobrist() ^ zobrist::turn(0) ^ zobrist::turn(1)
+    );
+    // cambiare il turno su una copia aggiorna anche l'hash
+    let turned = a.with_turn(1);
+    assert_eq!(turned.zobrist(), parsed.zobrist());
+    assert_eq!(turned.zobrist(), turned.compute_hash());
+    assert_eq!(turned.with_turn(0).zobrist(), a.zobrist());
+
+    let mut game = a;
+    game.add_atom((0, 0)).unwrap();
+    game.undo();
+    assert_eq!(game.zobrist(), b.zobrist());
+}
+
+#[test]
+fn transposition_table() {
+    let mut table = TranspositionTable::new(10);
+    assert_eq!(table.capacity(), 8);
+    assert!(table.is_empty());
+    assert_eq!(TranspositionTable::new(0).capacity(), 2);
+
+    table.store(3, 4, 10, Bound::Exact, Some((1, 2)));
+    let entry = table.probe(3).unwrap();
+    assert_eq!(
+        (entry.depth, entry.score, entry.bound, entry.best_move),
+        (4, 10, Bound::Exact, Some((1, 2)))
+    );
+    assert_eq!(table.probe(7), None);
+
+    // stesso bucket: la ricerca meno profonda finisce nel posto sempre rimpiazzabile
+    table.store(7, 2, -5, Bound::Upper, None);
+    table.store(11, 1, 0, Bound::Lower, None);
+    assert!(table.probe(3).is_some());
+    assert!(table.probe(7).is_none());
+    assert!(table.probe(11).is_some());
+    assert_eq!(table.len(), 2);
+
+    // aggiornare una posizione senza mossa migliore mantiene quella precedente
+    table.store(3, 5, 12, Bound::Lower, None);
+    assert_eq!(table.probe(3).unwrap().best_move, Some((1, 2)));
+    assert_eq!(table.len(), 2);
+
+    // in una nuova ricerca le voci vecchie sono rimpiazzabili anche se più profonde
+    table.new_search();
+    table.store(15, 1, 1, Bound::Exact, None);
+    assert!(table.probe(3).is_none());
+    assert!(table.probe(15).is_some());
+
+    table.clear();
+    assert!(table.is_empty());
+}
+
+#[test]
+fn symmetry() {
+    for (height, width) in [(3, 5), (4, 4)] {
+        for &symmetry in Symmetry::available(height, width) {
+            for row in 0..height {
+                for col in 0..width {
+                    let image = symmetry.apply((row, col), height, width);
+                    assert_eq!(symmetry.inverse().apply(image, height, width), (row, col));
+                }
+            }
+            // le simmetrie conservano la capacità delle celle
+            let game = Game::new(height, width, 2).unwrap();
+            assert_eq!(game.transform(symmetry).board, game.board);
+        }
+    }
+    assert_eq!(Symmetry::available(3, 5).len(), 4);
+    assert_eq!(Symmetry::available(4, 4).len(), 8);
+
+    let game: Game = "0 0 0 0\n0 0 0 0\n0 2b 0 0\n1a 0 0 0".parse().unwrap();
+    let (canonical, symmetry) = game.canonical();
+    assert_eq!(
+        format!("{:#}", canonical),
+        "players 2 turn a\n0 0 0 0\n0 0 0 0\n0 0 2b 0\n0 0 0 1a"
+    );
+    assert_eq!(symmetry, Symmetry::FlipHorizontal);
+    assert_eq!(symmetry.apply((3, 0), 4, 4), (3, 3));
+    assert_eq!(canonical.zobrist(), canonical.compute_hash());
+
+    // tutte le posizioni equivalenti hanno la stessa forma canonica
+    for &other in Symmetry::available(4, 4) {
+        let (image, to_canonical) = game.transform(other).canonical();
+        assert_eq!(image.board, canonical.board);
+        assert_eq!(image.zobrist(), canonical.zobrist());
+        // e le mosse si traducono passando per la forma canonica
+        let coord = other.apply((2, 1), 4, 4);
+        assert_eq!(to_canonical.apply(coord, 4, 4), (2, 2));
+    }
+}
+
+#[test]
+fn bitboard() {
+    let mut rng = StdRng::seed_from_u64(35);
+    for (height, width, players) in [(3, 3, 2), (5, 4, 3), (11, 6, 2), (18, 10, 8), (7, 9, 4)] {
+        for _ in 0..20 {
+            let mut game = Game::new(height, width, players).unwrap();
+            let mut fast = FastGame::new(height, width, players).unwrap();
+            while !game.won() {
+                let moves: Vec<_> = game.legal_moves().collect();
+                assert_eq!(fast.legal_moves().collect::<Vec<_>>(), moves);
+                assert_eq!(fast.legal_count(), moves.len());
+                let coord = *moves.choose(&mut rng).unwrap();
+                game.add_atom(coord).unwrap();
+                fast.add_atom(coord).unwrap();
+                for row in 0..height {
+                    for col in 0..width {
+                        let (expected, actual) = (game.get((row, col)), fast.get((row, col)));
+                        assert_eq!(expected.atoms(), actual.atoms());
+                        assert_eq!(expected.max_atoms(), actual.max_atoms());
+                        if expected.atoms() != 0 {
+                            assert_eq!(expected.player(), actual.player());
+                        }
+                    }
+                }
+                for player in 0..players {
+                    assert_eq!(fast.atoms(player), game.players[player].atoms);
+                }
+                assert_eq!(fast.turn(), game.turn());
+                assert_eq!(fast.won(), game.won());
+            }
+            assert!(fast.add_atom((0, 0)).is_err());
+            assert_eq!(fast.legal_moves().count(), 0);
+        }
+    }
+
+    // stesso risultato anche partendo da una posizione già avviata
+    let game: Game = "1a 2b 0\n2a 3b 1a\n0 1b 1b".parse().unwrap();
+    let mut fast = FastGame::from(&game);
+    let mut game = game;
+    game.add_atom((1, 0)).unwrap();
+    fast.add_atom((1, 0)).unwrap();
+    assert_eq!(fast.get((1, 1)), game.get((1, 1)));
+    assert!(fast.add_atom((1, 1)).is_err());
+}
+
+#[test]
+fn array() {
+    fn test<const H: usize, const W: usize>(players: usize, rng: &mut StdRng) {
+        for _ in 0..20 {
+            let mut game = Game::new(H, W, players).unwrap();
+            let mut array = ArrayGame::<H, W>::new(players).unwrap();
+            while !game.won() {
+                let moves: Vec<_> = game.legal_moves().collect();
+                assert_eq!(array.legal_moves().collect::<Vec<_>>(), moves);
+                let coord = *moves.choose(rng).unwrap();
+                let copy = array;
+                game.add_atom(coord).unwrap();
+                array.add_atom(coord).unwrap();
+                assert_ne!(copy, array);
+                for row in 0..H {
+                    for col in 0..W {
+                        assert_eq!(game.get((row, col)), array.get((row, col)));
+                    }
+                }
+                for player in 0..players {
+                    assert_eq!(array.atoms(player), game.players[player].atoms);
+                }
+                assert_eq!(array.turn(), game.turn());
+                assert_eq!(array.won(), game.won());
+            }
+            assert!(array.add_atom((0, 0)).is_err());
+        }
+    }
+    let mut rng = StdRng::seed_from_u64(36);
+    test::<3, 3>(2, &mut rng);
+    test::<5, 4>(3, &mut rng);
+    test::<11, 6>(2, &mut rng);
+    test::<18, 10>(8, &mut rng);
+
+    assert!(SmallGame::new(9).is_none());
+    assert_eq!(LargeGame::new(2).unwrap().height(), 18);
+    let game: Game = "1a 2b 0\n2a 3b 1a\n0 1b 1b".parse().unwrap();
+    assert!(ArrayGame::<3, 4>::from_game(&game).is_none());
+    let array = ArrayGame::<3, 3>::from_game(&game).unwrap();
+    assert_eq!(array.get((1, 1)), game.get((1, 1)));
+    assert_eq!(array.atoms(1), 7);
+}
+
+#[test]
+fn allocation_free() {
+    let mut game = Game::small(3);
+    let mut rng = StdRng::seed_from_u64(37);
+    let mut moves = vec![];
+    let mut undos = Vec::with_capacity(500);
+    // la prima partita fa crescere i buffer fino alla dimensione necessaria
+    while !game.won() {
+        let coord = *game
+            .legal_moves()
+            .collect::<Vec<_>>()
+            .choose(&mut rng)
+            .unwrap();
+        moves.push(coord);
+        undos.push(game.make_move(coord).unwrap());
+    }
+    while let Some(undo) = undos.pop() {
+        game.unmake_move(undo);
+    }
+    let before = allocations();
+    for &coord in &moves {
+        undos.push(game.make_move(coord).unwrap());
+    }
+    while let Some(undo) = undos.pop() {
+        game.unmake_move(undo);
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(game.atoms, 0);
+    // mentre la versione con cronologia e fotogrammi alloca ad ogni mossa
+    game.add_atom(moves[0]).unwrap();
+    assert!(allocations() > before);
+
+    // una copia della partita non si porta dietro i buffer
+    let copy = game.clone();
+    assert!(copy.scratch.exploded.is_empty());
+}
+
+#[test]
+fn baseline_bots() {
+    // stesso seme, stesse mosse
+    let game = Game::small(2);
+    let first: Vec<_> = (0..5)
+        .map(|_| RandomBot::new(7).choose_move(&game))
+        .collect();
+    assert!(first.iter().all(|&coord| coord == first[0]));
+
+    let game = Game::from_ascii("1a 0 0\n0 2b 0\n1b 0 1a", 2, 0).unwrap();
+    let mut random = RandomBot::new(38);
+    for _ in 0..20 {
+        assert!(game.is_legal(random.choose_move(&game)));
+    }
+    // l'unica mossa che cattura è quella nell'angolo che esplode verso il blu
+    let game = Game::from_ascii("1a 2b 0\n0 0 0\n0 0 1b", 2, 0).unwrap();
+    let mut greedy = GreedyBot::new(38);
+    assert_eq!(greedy.choose_move(&game), (0, 0));
+    // se il blu non ha altri atomi la stessa cattura vince la partita
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    assert_eq!(greedy.choose_move(&game), (2, 0));
+
+    // il bot avido batte quasi sempre quello casuale
+    let mut wins = 0;
+    for seed in 0..20 {
+        let mut bots: [Box<dyn Bot>; 2] = [
+            Box::new(GreedyBot::new(seed)),
+            Box::new(RandomBot::new(seed)),
+        ];
+        let mut game = Game::new(5, 5, 2).unwrap();
+        while !game.won() {
+            let coord = bots[game.turn()].choose_move(&game);
+            game.add_atom(coord).unwrap();
+        }
+        wins += (game.turn() == 0) as usize;
+    }
+    assert!(wins >= 15, "{}", wins);
+}
+
+#[test]
+fn search() {
+    // una vittoria immediata viene trovata da entrambe le strategie
+    let game = Game::from_ascii("1a 2b 0\n0 0 0\n0 0 1b", 2, 0).unwrap();
+    let mut results = vec![];
+    for strategy in [Strategy::Paranoid, Strategy::MaxN] {
+        let mut bot = SearchBot::new(strategy, Budget::nodes(20_000));
+        let result = bot.search(&Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap());
+        assert_eq!(result.best_move, (2, 0));
+        assert_eq!(result.score, WIN - 1);
+        // qui invece la cattura non basta a vincere subito
+        results.push(bot.search(&game));
+    }
+    // la vittoria arriva alla nona mossa, come dice la tabella dei finali; max^n non pota e con
+    // lo stesso budget non ci arriva, ma sceglie comunque la stessa mossa
+    let probe = Tablebase::generate(3, 3, 4).unwrap().probe(&game).unwrap();
+    assert_eq!(probe.value, Value::Win(9));
+    assert_eq!(
+        (results[0].best_move, results[0].score),
+        (probe.best_move, WIN - 9)
+    );
+    assert_eq!(
+        (results[1].best_move, results[1].score, results[1].depth),
+        (probe.best_move, 3, 5)
+    );
+
+    // il budget di nodi viene rispettato e la ricerca è deterministica
+    let game = Game::new(5, 5, 3).unwrap();
+    let mut bot = SearchBot::new(Strategy::Paranoid, Budget::nodes(3_000));
+    let first = bot.search(&game);
+    assert!(first.nodes <= 3_000 + 25);
+    assert!(first.depth >= 2);
+    let mut other = SearchBot::new(Strategy::Paranoid, Budget::nodes(3_000));
+    assert_eq!(other.search(&game), first);
+    let mut bot = SearchBot::new(Strategy::MaxN, Budget::time(Duration::from_millis(50)));
+    assert!(game.is_legal(bot.search(&game).best_move));
+    let mut bot = SearchBot::new(Strategy::MaxN, Budget::default());
+    bot.max_depth = 2;
+    assert_eq!(bot.search(&game).depth, 2);
+
+    // in due la ricerca batte il bot avido
+    let mut wins = 0;
+    for seed in 0..4 {
+        let mut bots: [Box<dyn Bot>; 2] = [
+            Box::new(SearchBot::new(Strategy::Paranoid, Budget::nodes(2_000))),
+            Box::new(GreedyBot::new(seed)),
+        ];
+        let mut game = Game::new(4, 4, 2).unwrap();
+        game.add_atom([(0, 0), (1, 1), (2, 3), (3, 2)][seed as usize])
+            .unwrap();
+        while !game.won() {
+            let coord = bots[game.turn()].choose_move(&game);
+            game.add_atom(coord).unwrap();
+        }
+        wins += (game.turn() == 0) as usize;
+    }
+    assert!(wins >= 3, "{}", wins);
+}
+
+#[test]
+fn mcts() {
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    for rollout in [Rollout::Random, Rollout::Heuristic] {
+        let mut bot = MctsBot::new(Budget::nodes(500), 40);
+        bot.rollout = rollout;
+        assert_eq!(bot.choose_move(&game), (2, 0));
+    }
+
+    // stesso seme, stessa mossa
+    let game = Game::new(5, 5, 3).unwrap();
+    let first = MctsBot::new(Budget::nodes(300), 1).choose_move(&game);
+    assert_eq!(
+        MctsBot::new(Budget::nodes(300), 1).choose_move(&game),
+        first
+    );
+
+    // dopo un giro di mosse l'albero della ricerca precedente viene riusato
+    let mut bot = MctsBot::new(Budget::nodes(2_000), 2);
+    let mut game = Game::new(4, 4, 2).unwrap();
+    let coord = bot.choose_move(&game);
+    game.add_atom(coord).unwrap();
+    let reply = game.legal_moves().next().unwrap();
+    game.add_atom(reply).unwrap();
+    bot.budget = Budget::nodes(100);
+    bot.choose_move(&game);
+    assert!(bot.visits() > 100);
+    let mut fresh = MctsBot::new(Budget::nodes(100), 2);
+    fresh.choose_move(&game);
+    assert_eq!(fresh.visits(), 100);
+
+    // con tre giocatori batte quasi sempre due bot casuali
+    let mut wins = 0;
+    for seed in 0..5 {
+        let mut bots: [Box<dyn Bot>; 3] = [
+            Box::new(MctsBot::new(Budget::nodes(1_000), seed)),
+            Box::new(RandomBot::new(seed)),
+            Box::new(RandomBot::new(seed + 100)),
+        ];
+        let mut game = Game::new(4, 4, 3).unwrap();
+        while !game.won() {
+            let coord = bots[game.turn()].choose_move(&game);
+            game.add_atom(coord).unwrap();
+        }
+        wins += (game.turn() == 0) as usize;
+    }
+    assert!(wins >= 4, "{}", wins);
+}
+
+#[test]
+fn evaluators() {
+    let game = Game::from_ascii("1a 2b 0 0\n0 3b 0 0\n0 2a 1a 1b", 2, 0).unwrap();
+    assert_eq!(Material.evaluate(&game, 0), 8 - 10);
+    assert_eq!(Material.evaluate(&game, 1), 12 - 10);
+    // critiche: a in (0, 0) e (2, 1), b in (0, 1), (1, 1) e (2, 3)
+    assert_eq!(Critical.evaluate(&game, 0), 2 - 3);
+    // minacciate: di a (0, 0), (2, 1) e (2, 2), di b (0, 1) e (1, 1)
+    assert_eq!(Threats.evaluate(&game, 0), 2 - 3);
+    assert_eq!(Threats.evaluate(&game, 1), 3 - 2);
+    // a: un angolo e due bordi, b: un bordo e un angolo
+    assert_eq!(Edges.evaluate(&game, 0), 4 - 3);
+    // il blu può catturare tutte e tre le celle del rosso, il rosso solo due del blu
+    assert_eq!(Unstable.evaluate(&game, 0), -3);
+    assert_eq!(Unstable.evaluate(&game, 1), -2);
+    let weighted = Weighted::new(vec![(2, Box::new(Material)), (-1, Box::new(Edges))]);
+    assert_eq!(weighted.evaluate(&game, 0), -4 - 1);
+    assert_eq!(Weighted::default().evaluate(&game, 0), 0);
+
+    // la ricerca accetta qualsiasi valutazione
+    let mut bot = SearchBot::with_evaluator(
+        Strategy::Paranoid,
+        Budget::nodes(2_000),
+        Weighted::standard(),
+    );
+    assert!(game.is_legal(bot.choose_move(&game)));
+    let mut bot = SearchBot::with_evaluator(Strategy::MaxN, Budget::nodes(500), Unstable);
+    assert!(game.is_legal(bot.choose_move(&game)));
+}
+
+#[test]
+fn difficulty() {
+    // (0, 0) fa partire una reazione che cattura il blu solo dal secondo round in poi, chi vede
+    // un round solo preferisce far esplodere direttamente (0, 1)
+    let game = Game::from_ascii("1a 2a 2b 0\n0 0 0 0\n0 0 1b 1a", 2, 0).unwrap();
+    assert!(game.preview((0, 0)).unwrap().explosions.len() > 1);
+    let profile = Profile {
+        engine: Engine::Shallow {
+            noise: 0,
+            horizon: None,
+        },
+        budget: Budget::default(),
+        blunder: 0.0,
+    };
+    assert_eq!(
+        DifficultyBot::with_profile(profile, 0).choose_move(&game),
+        (0, 0)
+    );
+    let short_sighted = Profile {
+        engine: Engine::Shallow {
+            noise: 0,
+            horizon: Some(1),
+        },
+        ..profile
+    };
+    assert_eq!(
+        DifficultyBot::with_profile(short_sighted, 0).choose_move(&game),
+        (0, 1)
+    );
+    // senza orizzonte nessuna esplosione è visibile e conta solo l'atomo aggiunto
+    let blind = Profile {
+        engine: Engine::Shallow {
+            noise: 0,
+            horizon: Some(0),
+        },
+        ..profile
+    };
+    assert!(game.is_legal(DifficultyBot::with_profile(blind, 0).choose_move(&game)));
+    // una reazione interrotta senza catture ha lo stesso materiale di quella completa
+    let quiet = Game::from_ascii("1a 2a 0 0\n0 0 0 0\n0 0 0 1b", 2, 0).unwrap();
+    let preview = quiet.preview((0, 0)).unwrap();
+    assert!(preview.explosions.len() > 1 && preview.captures.cells.is_empty());
+    for explosion in &preview.explosions {
+        let cut = quiet.with_board(explosion.result.clone());
+        assert_eq!(cut.atoms, preview.game.atoms);
+        assert_eq!(
+            Material.evaluate(&cut, 0),
+            Material.evaluate(&preview.game, 0)
+        );
+    }
+
+    // il rumore è limitato, dipende solo dalla posizione e cambia da una posizione all'altra
+    let noisy = Noisy {
+        evaluator: Material,
+        amplitude: 5,
+        seed: 9,
+    };
+    let mut offsets = HashSet::new();
+    for coord in game.legal_moves() {
+        let after = game.preview(coord).unwrap().game;
+        let offset = noisy.evaluate(&after, 0) - Material.evaluate(&after, 0);
+        assert!((-5..=5).contains(&offset));
+        assert_eq!(noisy.evaluate(&after, 0), noisy.evaluate(&after.clone(), 0));
+        offsets.insert(offset);
+    }
+    assert!(offsets.len() > 1);
+
+    // stesso seme, stesse mosse
+    let play = |difficulties: [Difficulty; 2], seed: u64, limit: usize| {
+        let mut bots = difficulties.map(|difficulty| DifficultyBot::new(difficulty, seed));
+        let mut game = Game::new(4, 4, 2).unwrap();
+        let mut moves = vec![];
+        while !game.won() && moves.len() < limit {
+            let coord = bots[game.turn()].choose_move(&game);
+            moves.push(coord);
+            game.add_atom(coord).unwrap();
+        }
+        (moves, game.turn())
+    };
+    for difficulty in Difficulty::ALL {
+        let pair = [difficulty, Difficulty::Beginner];
+        // Monte Carlo è lento senza ottimizzazioni, basta la prima mossa
+        let limit = if difficulty >= Difficulty::Hard { 1 } else { 6 };
+        assert_eq!(play(pair, 42, limit), play(pair, 42, limit));
+    }
+    let wins = (0..4)
+        .filter(|&seed| play([Difficulty::Medium, Difficulty::Beginner], seed, usize::MAX).1 == 0)
+        .count();
+    assert!(wins >= 3, "{}", wins);
+}
+
+#[test]
+fn solver() {
+    let mut solver = Solver::default();
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    assert_eq!(solver.outcome(&game), Some(Outcome::Win));
+    // dopo una mossa qualsiasi del blu il rosso vince subito
+    let game = Game::from_ascii("1b 0 0\n0 0 0\n1a 2b 0", 2, 1).unwrap();
+    let solution = solver.solve(&game).unwrap();
+    assert_eq!(solution.outcome, Outcome::Win);
+    assert!(solution.moves.contains(&(2, 1)));
+
+    // la posizione iniziale del 3x3 è vinta dal primo giocatore con qualsiasi mossa
+    let game = Game::new(3, 3, 2).unwrap();
+    let solution = solver.solve(&game).unwrap();
+    assert_eq!(solution.outcome, Outcome::Win);
+    assert_eq!(solution.moves.len(), 9);
+    for coord in solution.moves {
+        let mut reply = game.clone();
+        reply.add_atom(coord).unwrap();
+        let solution = solver.solve(&reply).unwrap();
+        assert_eq!(solution.outcome, Outcome::Loss);
+        // in una posizione persa tutte le mosse sono equivalenti
+        assert_eq!(solution.moves.len(), reply.legal_moves().count());
+    }
+
+    assert_eq!(Solver::new(Budget::nodes(10)).solve(&game), None);
+    assert_eq!(Solver::default().solve(&Game::new(3, 3, 3).unwrap()), None);
+}
+
+#[test]
+fn tablebase() {
+    let tablebase = Tablebase::generate(3, 3, 8).unwrap();
+    assert!(!tablebase.is_empty());
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let probe = tablebase.probe(&game).unwrap();
+    assert_eq!(probe.value, Value::Win(1));
+    assert_eq!(probe.best_move, (2, 0));
+    // la mossa viene riportata nelle coordinate della posizione interrogata
+    let flipped = tablebase
+        .probe(&game.transform(Symmetry::FlipHorizontal))
+        .unwrap();
+    assert_eq!(flipped.best_move, (2, 2));
+    assert_eq!(tablebase.probe(&Game::new(3, 3, 2).unwrap()), None);
+    assert_eq!(tablebase.probe(&Game::new(3, 4, 2).unwrap()), None);
+
+    // i valori sono coerenti con la miglior mossa e con il risolutore
+    let mut solver = Solver::default();
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..10 {
+        let mut game = Game::new(3, 3, 2).unwrap();
+        game.add_atom((0, 0)).unwrap();
+        game.add_atom((1, 1)).unwrap();
+        while let Some(probe) = tablebase.probe(&game) {
+            let outcome = match probe.value {
+                Value::Win(_) => Outcome::Win,
+                Value::Loss(_) => Outcome::Loss,
+            };
+            assert_eq!(solver.outcome(&game), Some(outcome));
+            let mut child = game.clone();
+            child.add_atom(probe.best_move).unwrap();
+            match (probe.value, tablebase.probe(&child)) {
+                (Value::Win(1), _) => assert!(child.won()),
+                (Value::Win(n), Some(reply)) => assert_eq!(reply.value, Value::Loss(n - 1)),
+                (Value::Loss(n), Some(reply)) => assert_eq!(reply.value, Value::Win(n - 1)),
+                _ => {}
+            }
+            let moves: Vec<_> = game.legal_moves().collect();
+            game.add_atom(*moves.choose(&mut rng).unwrap()).unwrap();
+        }
+    }
+
+    let mut bytes = vec![];
+    tablebase.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 13 + 11 * tablebase.len());
+    assert_eq!(Tablebase::read_from(&bytes[..]).unwrap(), tablebase);
+    bytes[0] = b'X';
+    assert!(Tablebase::read_from(&bytes[..]).is_err());
+    assert!(Tablebase::read_from(&bytes[..20]).is_err());
+
+    let mut bot = SearchBot::new(Strategy::Paranoid, Budget::nodes(1_000));
+    bot.tablebase = Some(Arc::new(tablebase));
+    let result = bot.search(&game);
+    assert_eq!((result.best_move, result.score), ((2, 0), WIN - 1));
+}
+
+#[test]
+fn perft() {
+    // i conteggi di riferimento abbastanza piccoli da verificare anche senza ottimizzazioni
+    for known in KNOWN {
+        let game = Game::new(known.height, known.width, known.players).unwrap();
+        let fast = FastGame::from(&game);
+        for (depth, &count) in known.counts.iter().enumerate() {
+            if count > 100_000 {
+                break;
+            }
+            assert_eq!(game.perft(depth + 1), count);
+            assert_eq!(fast.perft(depth + 1), count);
+        }
+    }
+    let game = Game::new(3, 3, 2).unwrap();
+    assert_eq!(game.perft(0), 1);
+    assert_eq!(ArrayGame::<3, 3>::new(2).unwrap().perft(5), 28_312);
+    assert_eq!(ArrayGame::<4, 4>::new(2).unwrap().perft(4), 50_468);
+    assert_eq!(SmallGame::new(2).unwrap().perft(3), 278_850);
+
+    let divide = game.divide(4);
+    assert_eq!(divide.len(), 9);
+    assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 4_016);
+    // le mosse nella stessa classe di simmetria hanno lo stesso sottoalbero
+    assert_eq!(divide[0].1, divide[8].1);
+    assert_eq!(divide[1].1, divide[3].1);
+
+    // dopo due mosse ogni sequenza porta in una posizione diversa, dopo tre no
+    assert_eq!(game.perft_positions(2), 72);
+    assert!(game.perft_positions(3) < 576);
+    assert_eq!(game.perft_positions(3), 300);
+
+    // le partite vinte non si estendono oltre
+    let mut game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let perft = game.perft(2);
+    game.add_atom((2, 0)).unwrap();
+    assert_eq!(game.perft(1), 0);
+    assert!(perft < 8 * 8);
+}
+
+#[test]
+fn hint() {
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let hint = analysis::hint::hint(&game, Budget::nodes(1_000)).unwrap();
+    assert_eq!(hint.best_move, (2, 0));
+    assert!(hint.wins);
+    assert_eq!(hint.captured, vec![(2, 1)]);
+    assert_eq!(hint.chain, 2);
+    // il 2 blu in basso minacciava l'atomo rosso nell'angolo
+    assert_eq!(hint.neutralised, vec![(2, 1)]);
+
+    // senza limiti nel budget la ricerca si ferma comunque
+    let mut game = Game::new(4, 4, 2).unwrap();
+    game.add_atom((1, 1)).unwrap();
+    let hint = analysis::hint::hint(&game, Budget::default()).unwrap();
+    assert!(game.is_legal(hint.best_move));
+    assert!(!hint.wins);
+    assert_eq!((hint.captured.len(), hint.chain), (0, 0));
+    assert!(hint.neutralised.is_empty());
+
+    // lo stesso bot, e la sua tabella, servono per più suggerimenti di fila
+    let mut bot = analysis::hint::hinter(Budget::default());
+    assert_eq!(analysis::hint::hint_with(&game, &mut bot), Some(hint));
+    let mut won = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    won.add_atom((2, 0)).unwrap();
+    assert_eq!(analysis::hint::hint_with(&won, &mut bot), None);
+    assert_eq!(analysis::hint::hint(&won, Budget::nodes(1_000)), None);
+}
+
+#[test]
+fn threats() {
+    let game = Game::from_ascii("1a 0 0 1a\n0 3b 2a 0\n0 0 0 0\n1b 0 0 0", 2, 0).unwrap();
+    let threats = game.threats();
+    assert_eq!(threats.len(), 2);
+    // gli angoli rossi sono critici ma non toccano nessuna cella blu
+    let cells: Vec<_> = threats[0].critical.iter().map(|c| c.cell).collect();
+    assert_eq!(cells, vec![(0, 0), (0, 3)]);
+    assert!(threats[0].critical.iter().all(|c| c.captures.is_empty()));
+    assert_eq!(threats[0].vulnerable, vec![(1, 2)]);
+    assert_eq!(threats[0].max_chain, 1);
+    assert_eq!(
+        threats[1].critical[0],
+        CriticalCell {
+            cell: (1, 1),
+            captures: vec![(1, 2)],
+            chain: 1,
+        }
+    );
+    assert!(threats[1].vulnerable.is_empty());
+
+    // la cattura innesca una seconda esplosione
+    let game = Game::from_ascii("1a 2b 0\n0 0 0\n0 0 0", 2, 0).unwrap();
+    let threats = game.threats();
+    assert_eq!(threats[0].critical[0].captures, vec![(0, 1)]);
+    assert_eq!(threats[0].max_chain, 2);
+    assert_eq!(threats[0].vulnerable, vec![(0, 0)]);
+    assert_eq!(threats[1].vulnerable, vec![(0, 1)]);
+    // l'analisi non cambia la partita
+    assert_eq!(game.turn(), 0);
+
+    let mut game = game;
+    game.add_atom((0, 0)).unwrap();
+    assert!(game.won());
+    assert!(game
+        .threats()
+        .iter()
+        .all(|t| *t == PlayerThreats::default()));
+}
+
+#[test]
+fn impacts() {
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let impacts = game.impacts();
+    assert_eq!(impacts.len(), 8);
+    assert_eq!(
+        impacts[0],
+        Impact {
+            coord: (2, 0),
+            rounds: 2,
+            exploded: 2,
+            captured: vec![0, 2],
+            eliminated: vec![1],
+            wins: true,
+        }
+    );
+    // le mosse tranquille restano nell'ordine di `legal_moves`
+    assert_eq!(impacts[1].coord, (0, 0));
+    assert!(impacts[1..].iter().all(|impact| impact.rounds == 0));
+    assert_eq!(game.turn(), 0);
+
+    // le misure coincidono con quelle dell'anteprima
+    let mut game = Game::new(5, 5, 3).unwrap();
+    let mut bot = RandomBot::new(3);
+    for _ in 0..30 {
+        let coord = bot.choose_move(&game);
+        game.add_atom(coord).unwrap();
+    }
+    let impacts = game.impacts();
+    assert!(impacts[0].rounds > 0);
+    for impact in impacts {
+        let preview = game.preview(impact.coord).unwrap();
+        let exploded: HashSet<_> = preview
+            .explosions
+            .iter()
+            .flat_map(|explosion| explosion.exploded.iter())
+            .collect();
+        assert_eq!(impact.rounds, preview.explosions.len());
+        assert_eq!(impact.exploded, exploded.len());
+        assert_eq!(impact.captured, preview.captures.atoms);
+        assert_eq!(impact.eliminated, preview.captures.eliminated);
+        assert_eq!(impact.wins, preview.game.won());
+    }
+}
+
+#[test]
+fn report() {
+    let start = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let mut analyser = Analyser::new(Budget::nodes(2_000));
+    let report = analyser.analyse(&start, &[(0, 0), (2, 1)]).unwrap();
+    assert_eq!(report.moves.len(), 2);
+    let first = &report.moves[0];
+    assert_eq!((first.ply, first.player, first.coord), (1, 0, (0, 0)));
+    assert_eq!((first.best_move, first.best_score), ((2, 0), WIN - 1));
+    assert_eq!(first.annotation, Annotation::Blunder);
+    assert!(first.missed_win);
+    assert_eq!(report.moves[1].annotation, Annotation::Best);
+    assert_eq!(report.winner, None);
+    let text = report.to_string();
+    assert_eq!(text.lines().count(), 3);
+    assert!(text
+        .lines()
+        .next()
+        .unwrap()
+        .contains("best (2, 0) win in 1  missed win"));
+    assert!(text.ends_with("unfinished"));
+    #[cfg(feature = "serde")]
+    assert!(report.to_json().contains(r#""annotation": "Blunder""#));
+
+    // una partita completa tra due bot
+    let start = Game::new(3, 3, 2).unwrap();
+    let mut game = start.clone();
+    let mut bots: [Box<dyn Bot>; 2] = [Box::new(GreedyBot::new(1)), Box::new(RandomBot::new(2))];
+    let mut moves = vec![];
+    while !game.won() {
+        let coord = bots[game.turn()].choose_move(&game);
+        moves.push(coord);
+        game.add_atom(coord).unwrap();
+    }
+    let report = analyser.analyse(&start, &moves).unwrap();
+    assert_eq!(report.moves.len(), moves.len());
+    assert_eq!(report.winner, Some(game.turn()));
+    assert_eq!(report.moves.last().unwrap().score, WIN - 1);
+    for report in &report.moves {
+        assert!(report.score <= report.best_score || report.annotation == Annotation::Best);
+    }
+
+    // la ricerca della sola mossa giocata non lascia nella tabella un valore per l'intera
+    // posizione; a profondità 1 la radice è l'unica voce che potrebbe essere salvata
+    let mut bot = SearchBot::new(Strategy::Paranoid, Budget::default());
+    bot.max_depth = 1;
+    let mut position = Game::new(3, 3, 2).unwrap();
+    bot.search_moves(&mut position, &[(1, 1)], Budget::default());
+    assert!(bot.table.is_empty());
+    let all: Vec<_> = position.legal_moves().collect();
+    bot.search_moves(&mut position, &all, Budget::default());
+    assert_eq!(bot.table.len(), 1);
+
+    // lo stesso analizzatore, usato più volte, dà gli stessi resoconti di uno nuovo
+    let fixed = || {
+        let mut bot = SearchBot::new(Strategy::Paranoid, Budget::nodes(u64::MAX));
+        bot.max_depth = 3;
+        Analyser::with_bot(bot)
+    };
+    let mut reused = fixed();
+    for seed in 0..40 {
+        let start = Game::new(4, 4, 2).unwrap();
+        let mut game = start.clone();
+        let mut bot = RandomBot::new(seed);
+        let mut moves = vec![];
+        while !game.won() {
+            let coord = bot.choose_move(&game);
+            moves.push(coord);
+            game.add_atom(coord).unwrap();
+        }
+        let report = reused.analyse(&start, &moves).unwrap();
+        assert_eq!(reused.analyse(&start, &moves).unwrap(), report);
+        assert_eq!(fixed().analyse(&start, &moves).unwrap(), report);
+    }
+
+    // le mosse dopo la fine della partita o su celle avversarie sono errori
+    assert!(analyser.analyse(&start, &[(0, 0), (0, 0)]).is_err());
+    moves.push((0, 0));
+    assert!(analyser.analyse(&start, &moves).is_err());
+}
+
+#[test]
+fn odds() {
+    let game = Game::from_ascii("0 0 0\n0 0 0\n1a 2b 0", 2, 0).unwrap();
+    let mut estimator = Estimator::new(400, 1);
+    let estimate = estimator.estimate(&game);
+    assert_eq!(estimate.playouts, 400);
+    assert_eq!(
+        estimate.players.iter().map(|odds| odds.wins).sum::<u32>(),
+        400
+    );
+    for odds in &estimate.players {
+        assert_eq!(odds.probability, odds.wins as f64 / 400.0);
+        assert!(odds.low <= odds.probability && odds.probability <= odds.high);
+        assert!(odds.high - odds.low < 0.1);
+    }
+    assert_eq!(estimator.estimate(&game), estimate);
+    // con le esplosioni preferite il rosso trova più spesso la mossa vincente
+    estimator.rollout = Rollout::Heuristic;
+    let heuristic = estimator.estimate(&game);
+    assert!(heuristic.players[0].probability > estimate.players[0].probability);
+    // meno partite, intervalli più larghi
+    let few = Estimator::new(20, 1).estimate(&game);
+    assert!(
+        few.players[0].high - few.players[0].low
+            > estimate.players[0].high - estimate.players[0].low
+    );
+
+    let start = Game::new(3, 3, 2).unwrap();
+    let moves = [
+        (2, 1),
+        (0, 2),
+        (2, 1),
+        (2, 2),
+        (2, 1),
+        (0, 2),
+        (0, 2),
+        (1, 2),
+        (0, 2),
+        (2, 2),
+        (0, 2),
+    ];
+    let timeline = Estimator::new(50, 7).timeline(&start, &moves).unwrap();
+    assert_eq!(timeline.len(), moves.len() + 1);
+    let mut game = start.clone();
+    for &coord in &moves {
+        game.add_atom(coord).unwrap();
+    }
+    let last = timeline.last().unwrap();
+    assert!(game.won());
+    // come per ogni altra stima, le probabilità sono vittorie su partite
+    assert_eq!(last.playouts, 1);
+    assert_eq!(
+        last.players
+            .iter()
+            .map(|odds| (odds.wins, odds.probability))
+            .collect::<Vec<_>>(),
+        vec![(1, 1.0), (0, 0.0)]
+    );
+    assert!(Estimator::new(50, 7)
+        .timeline(&start, &[(0, 0), (0, 0)])
+        .is_err());
+}
+
+#[test]
+fn terminal() {
+    let mut game: Game = "1a 0 0\n0 0 0\n0 0 1b".parse().unwrap();
+    let explosions = game.add_atom((0, 0)).unwrap();
+    let renderer = Terminal {
+        capacity: true,
+        critical: true,
+        ..Terminal::new()
+    }
+    .after_move((0, 0), &explosions);
+    let rendered = renderer.render(&game);
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines.len(), 3);
+    // la cella giocata ed esplosa è evidenziata, quelle raggiunte dall'esplosione non sono critiche
+    assert!(lines[0].starts_with("\x1b[2;48;5;238;7m0/2\x1b[0m "));
+    assert!(lines[0].contains("\x1b[38;2;230;57;70m1/3\x1b[0m"));
+    // l'angolo del blu ha un atomo su due ed è critico
+    assert!(lines[2].ends_with("\x1b[38;2;69;123;230;1;4m1/2\x1b[0m"));
+
+    let frame = Terminal::new().render_explosion(&explosions[0]);
+    assert!(frame.starts_with("\x1b[2;48;5;238m0\x1b[0m"));
+    assert_eq!(frame.lines().count(), 3);
+}
+
+#[test]
+fn svg() {
+    let mut game: Game = "1a 0 0\n0 2b 0\n0 0 0".parse().unwrap();
+    let image = Svg::new().render(&game);
+    assert!(
+        image.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="120""#)
+    );
+    assert!(image.ends_with("</svg>"));
+    assert_eq!(image.matches("<circle").count(), 3);
+    assert_eq!(image.matches("url(#orb1)").count(), 2);
+
+    let explosions = game.add_atom((0, 0)).unwrap();
+    let frames = Svg { cell_size: 20 }.render_cascade(&explosions);
+    assert_eq!(frames.len(), 1);
+    assert!(frames[0].contains(r#"height="84""#));
+    assert!(frames[0].contains(">Round 1</text>"));
+    assert_eq!(frames[0].matches(r##"stroke="#ff9800""##).count(), 1);
+    assert_eq!(frames[0].matches("url(#orb0)").count(), 2);
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn json() {
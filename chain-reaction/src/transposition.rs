@@ -0,0 +1,122 @@
+use std::mem::size_of;
+
+use crate::Coord;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    // il punteggio vero è almeno `score` (taglio beta)
+    Lower,
+    // il punteggio vero è al massimo `score` (nessuna mossa ha superato alpha)
+    Upper,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: u8,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Coord>,
+    generation: u8,
+}
+
+// ogni bucket ha due posti: il primo tiene la ricerca più profonda (a meno che non sia di una
+// ricerca precedente), il secondo viene sempre sovrascritto
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    buckets: Vec<[Option<Entry>; 2]>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    // il numero di bucket viene arrotondato per difetto a una potenza di 2
+    pub fn new(entries: usize) -> Self {
+        let buckets = (entries / 2).max(1);
+        let buckets = if buckets.is_power_of_two() {
+            buckets
+        } else {
+            buckets.next_power_of_two() / 2
+        };
+        Self {
+            buckets: vec![[None; 2]; buckets],
+            generation: 0,
+        }
+    }
+
+    pub fn with_memory(bytes: usize) -> Self {
+        Self::new(bytes / size_of::<Option<Entry>>())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * 2
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().flatten().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.buckets[self.index(key)]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key)
+            .copied()
+    }
+
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u8,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Coord>,
+    ) {
+        let generation = self.generation;
+        let index = self.index(key);
+        let bucket = &mut self.buckets[index];
+        let entry = Entry {
+            key,
+            depth,
+            score,
+            bound,
+            // se la nuova ricerca non ha trovato una mossa si tiene quella vecchia
+            best_move: best_move.or_else(|| {
+                bucket
+                    .iter()
+                    .flatten()
+                    .find(|entry| entry.key == key)
+                    .and_then(|entry| entry.best_move)
+            }),
+            generation,
+        };
+        let slot = match bucket[0] {
+            None => 0,
+            Some(old) if old.key == key || old.generation != generation || depth >= old.depth => 0,
+            _ => 1,
+        };
+        bucket[slot] = Some(entry);
+        // evita di avere due copie della stessa posizione nel bucket
+        if slot == 0 && matches!(bucket[1], Some(old) if old.key == key) {
+            bucket[1] = None;
+        }
+    }
+
+    // da chiamare all'inizio di ogni ricerca, così le voci vecchie possono essere rimpiazzate
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.fill([None; 2]);
+        self.generation = 0;
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key as usize) & (self.buckets.len() - 1)
+    }
+}
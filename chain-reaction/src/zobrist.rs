@@ -0,0 +1,44 @@
+use crate::Cell;
+
+const MAX_CELLS: usize = 18 * 10;
+const PLAYERS: usize = 8;
+// durante una reazione una cella può arrivare temporaneamente fino a 7 atomi
+const ATOMS: usize = 8;
+
+static CELLS: [u64; MAX_CELLS * PLAYERS * ATOMS] = generate(0x2545_f491_4f6c_dd1d);
+static TURNS: [u64; PLAYERS] = generate(0x9e6c_63d0_676a_9a99);
+
+// chiavi pseudo-casuali generate a tempo di compilazione, così sono identiche tra un'esecuzione
+// e l'altra e gli hash si possono salvare su file
+const fn generate<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = seed;
+    let mut i = 0;
+    while i < N {
+        // splitmix64
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        keys[i] = mix(state);
+        i += 1;
+    }
+    keys
+}
+
+// il passo finale di splitmix64: sparpaglia i bit di `z` su tutto il risultato
+pub(crate) const fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// le celle vuote non contribuiscono all'hash, indipendentemente dal giocatore salvato
+pub(crate) fn cell(index: usize, cell: Cell) -> u64 {
+    if cell.atoms() == 0 {
+        0
+    } else {
+        CELLS[(index * PLAYERS + cell.player()) * ATOMS + cell.atoms() as usize]
+    }
+}
+
+pub(crate) fn turn(turn: usize) -> u64 {
+    TURNS[turn]
+}